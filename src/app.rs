@@ -0,0 +1,43 @@
+use colored::Colorize;
+use crate::util::error_exit;
+use crate::input::cli::{parse_cli_arguments, Cli, Command, OutputFormat};
+
+fn print_banner() {
+    let banner = r#"
+                                __               __
+        ____  ____ ______      / /_  ____ ______/ /_
+       / __ \/ __ `/ ___/_____/ __ \/ __ `/ ___/ __ \
+      / /_/ / /_/ / /  /_____/ / / / /_/ (__  ) / / /
+     / .___/\__,_/_/        /_/ /_/\__,_/____/_/ /_/
+    /_/"#.bold().magenta();
+
+    println!("{banner}\n");
+}
+
+/// Run the command line interface. Hash functions registered before are selectable like the
+/// built-in ones.
+pub fn run() {
+
+    let cli: Cli = parse_cli_arguments();
+
+    if let Some(threads) = cli.options.threads {
+        rayon::ThreadPoolBuilder::new().num_threads(threads).build_global().unwrap_or_else(|e| {
+            error_exit(Some(format!("Unable to initialize thread pool with {threads} threads: {e:?}")));
+        });
+    }
+
+    // Keep stdout parsable when machine-readable output is requested
+    if cli.options.output_format == OutputFormat::TEXT {
+        print_banner();
+    }
+
+    match &cli.command {
+        Some(Command::Hash(args)) => crate::commands::hash::run(&cli.options, args),
+        Some(Command::Verify(args)) => crate::commands::verify::run(&cli.options, args),
+        Some(Command::Manifest(args)) => crate::commands::manifest::run(&cli.options, args),
+        Some(Command::Diff(args)) => crate::commands::diff::run(&cli.options, args),
+        Some(Command::Dupes(args)) => crate::commands::dupes::run(&cli.options, args),
+        Some(Command::Bench(args)) => crate::commands::bench::run(&cli.options, args),
+        None => crate::commands::hash::run(&cli.options, &cli.hash),
+    }
+}
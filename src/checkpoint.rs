@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::{File, Metadata};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::hasher::HasherFactory;
+use crate::models::{HashFunctionType, HashedEntry, HashingConfig, TargetType};
+use crate::observer::HashObserver;
+
+/// Version of the checkpoint file format, bumped whenever the layout changes incompatibly
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+/// Message whose keyed hash identifies the key of a checkpoint without revealing it
+const KEY_FINGERPRINT_LABEL: &[u8] = b"par-hash checkpoint key fingerprint";
+
+/// Hash values of all files that were completely hashed during an unfinished run. Directories are
+/// not stored, as their hash values are cheaply derived from their entries when resuming.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub algorithm: HashFunctionType,
+    pub config: HashingConfig,
+    pub target: PathBuf,
+
+    /// Keyed hash of a fixed label, so resuming with a different key is detected. Empty if no key
+    /// is used.
+    #[serde(with = "hex", default, skip_serializing_if = "Vec::is_empty")]
+    pub key_fingerprint: Vec<u8>,
+
+    pub files: HashMap<PathBuf, CheckpointedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointedFile {
+    pub size: u64, // in bytes
+    pub modified: u128, // in nanoseconds since the unix epoch
+    pub entry: HashedEntry,
+}
+
+impl Checkpoint {
+
+    pub fn new(algorithm: HashFunctionType, config: HashingConfig, target: PathBuf, key: Option<&[u8]>) -> Result<Self, String> {
+        Ok(Checkpoint {
+            version: CHECKPOINT_FORMAT_VERSION,
+            key_fingerprint: get_key_fingerprint(&algorithm, key)?,
+            algorithm,
+            config,
+            target,
+            files: HashMap::new()
+        })
+    }
+
+    /// Whether the checkpoint was created with the given key, or without a key if none is given
+    pub fn is_created_with_key(&self, key: Option<&[u8]>) -> bool {
+        get_key_fingerprint(&self.algorithm, key).is_ok_and(|fingerprint| fingerprint == self.key_fingerprint)
+    }
+
+    /// Get the stored hash tree of the file, if the file was not modified since it was hashed
+    pub fn get_file_entry(&self, path: &Path, metadata: &Metadata) -> Option<&HashedEntry> {
+        let file: &CheckpointedFile = self.files.get(path)?;
+
+        if file.size == metadata.len() && Some(file.modified) == get_modification_time(metadata) {
+            Some(&file.entry)
+        } else {
+            None
+        }
+    }
+}
+
+/// Write the checkpoint as compact JSON to the given file. The checkpoint is written to a temporary
+/// file first, so a crash while writing does not corrupt the previous checkpoint.
+pub fn export_checkpoint(path: &Path, checkpoint: &Checkpoint) -> std::io::Result<()> {
+    let mut temp_path: PathBuf = path.to_path_buf();
+    temp_path.as_mut_os_string().push(".tmp");
+
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(&temp_path)?);
+
+    serde_json::to_writer(&mut writer, checkpoint).map_err(|e| {
+        Error::other(format!("Unable to serialize checkpoint: {e:?}"))
+    })?;
+
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+pub fn import_checkpoint(path: &Path) -> std::io::Result<Checkpoint> {
+    let reader: BufReader<File> = BufReader::new(File::open(path)?);
+
+    let checkpoint: Checkpoint = serde_json::from_reader(reader).map_err(|e| {
+        Error::new(ErrorKind::InvalidData, format!("Unable to parse checkpoint: {e:?}"))
+    })?;
+
+    if checkpoint.version != CHECKPOINT_FORMAT_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unsupported checkpoint format version {} (expected {CHECKPOINT_FORMAT_VERSION})", checkpoint.version)
+        ));
+    }
+
+    Ok(checkpoint)
+}
+
+fn get_key_fingerprint(algorithm: &HashFunctionType, key: Option<&[u8]>) -> Result<Vec<u8>, String> {
+    match key {
+        Some(key) => Ok(HasherFactory::get_keyed_instance(algorithm, key)?.compute_hash(&mut KEY_FINGERPRINT_LABEL.to_vec())),
+        None => Ok(vec![])
+    }
+}
+
+fn get_modification_time(metadata: &Metadata) -> Option<u128> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| modified.as_nanos())
+}
+
+/// Observer that records every completely hashed file and periodically writes a checkpoint. The
+/// checkpoint is also written if the computation is cancelled or fails and once it is dropped.
+/// Serializing and syncing large checkpoints takes a while, so they are written by a dedicated
+/// thread instead of the hashing workers, which only hand over the finished files.
+pub struct CheckpointWriter {
+    sender: Option<Sender<CheckpointMessage>>,
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+enum CheckpointMessage {
+    STARTED { path: PathBuf, modified: Option<u128> }, // modification time when hashing of the file started
+    FINISHED { path: PathBuf, entry: HashedEntry },
+    FLUSH, // write the checkpoint without waiting for the interval to pass
+}
+
+impl CheckpointWriter {
+
+    /// Continue the given checkpoint, which may already contain files of a previous run
+    pub fn new(path: PathBuf, interval: Duration, checkpoint: Checkpoint) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let writer_thread: JoinHandle<()> = thread::spawn(move || write_checkpoints(&path, interval, checkpoint, receiver));
+
+        CheckpointWriter {
+            sender: Some(sender),
+            writer_thread: Some(writer_thread)
+        }
+    }
+
+    fn send(&self, message: CheckpointMessage) {
+        if let Some(sender) = &self.sender {
+            // The writer thread only terminates once the sender is dropped
+            sender.send(message).unwrap_or(());
+        }
+    }
+}
+
+/// Collect the finished files and write the checkpoint whenever the interval passed, until the
+/// CheckpointWriter is dropped
+fn write_checkpoints(path: &Path, interval: Duration, mut checkpoint: Checkpoint, receiver: Receiver<CheckpointMessage>) {
+    let mut started_files: HashMap<PathBuf, Option<u128>> = HashMap::new();
+    let mut last_write: Instant = Instant::now();
+    let mut modified_since_write: bool = false;
+
+    let write = |checkpoint: &Checkpoint| {
+        // Failing to write a checkpoint must not abort the hashing process
+        export_checkpoint(path, checkpoint).unwrap_or_else(|e| {
+            eprintln!("Unable to write checkpoint to '{:?}': {e:?}", path);
+        });
+    };
+
+    loop {
+        // Without pending changes there is nothing to write, so the next message is awaited indefinitely
+        let message: Result<CheckpointMessage, RecvTimeoutError> = if modified_since_write {
+            receiver.recv_timeout(interval.saturating_sub(last_write.elapsed()))
+        } else {
+            receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        };
+
+        let flush: bool = match message {
+            Ok(CheckpointMessage::STARTED { path, modified }) => {
+                started_files.insert(path, modified);
+                false
+            },
+            Ok(CheckpointMessage::FINISHED { path, entry }) => {
+                if let Some(Some(modified)) = started_files.remove(&path) {
+                    checkpoint.files.insert(path, CheckpointedFile { size: entry.size, modified, entry });
+                    modified_since_write = true;
+                }
+                false
+            },
+            Ok(CheckpointMessage::FLUSH) => true,
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => {
+                write(&checkpoint);
+                return;
+            }
+        };
+
+        if modified_since_write && (flush || last_write.elapsed() >= interval) {
+            write(&checkpoint);
+            last_write = Instant::now();
+            modified_since_write = false;
+        }
+    }
+}
+
+impl HashObserver for CheckpointWriter {
+
+    /// The modification time is determined before hashing, so modifications during hashing
+    /// invalidate the stored hash value
+    fn on_entry_started(&self, path: &Path, target_type: TargetType, _size: u64) {
+        if target_type == TargetType::FILE {
+            let modified: Option<u128> = path.metadata().ok().as_ref().and_then(get_modification_time);
+            self.send(CheckpointMessage::STARTED { path: path.to_path_buf(), modified });
+        }
+    }
+
+    fn on_entry_finished(&self, path: &Path, entry: &HashedEntry) {
+        self.send(CheckpointMessage::FINISHED { path: path.to_path_buf(), entry: entry.clone() });
+    }
+
+    fn on_error(&self, _path: &Path, _error: &Error) {
+        self.send(CheckpointMessage::FLUSH);
+    }
+
+    fn on_cancelled(&self, _path: &Path) {
+        self.send(CheckpointMessage::FLUSH);
+    }
+}
+
+/// Write the final checkpoint and wait for it to be written
+impl Drop for CheckpointWriter {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+
+        if let Some(writer_thread) = self.writer_thread.take() {
+            writer_thread.join().unwrap_or_else(|_| {
+                eprintln!("An error occurred while waiting for the checkpoint writer thread!");
+            });
+        }
+    }
+}
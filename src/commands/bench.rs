@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::time::Instant;
+use serde::Serialize;
+use crate::commands::{exit_on_hashing_error, get_cancellation_token, get_hash_key, get_hashing_config, print_json, print_status};
+use crate::hasher::HasherFactory;
+use crate::hashing::HashComputer;
+use crate::input::cli::{BenchArgs, GlobalOptions, OutputFormat};
+use crate::input::input::{get_hash_functions, validate_hash_target};
+use crate::models::HashFunctionType;
+use crate::util::error_exit;
+use crate::util::math::{mb_to_bytes, ratio};
+
+#[derive(Serialize)]
+struct BenchmarkResult {
+    algorithm: HashFunctionType,
+    bytes: u64,
+    seconds: f64,
+    throughput: f64, // in MB/s
+}
+
+pub fn run(options: &GlobalOptions, args: &BenchArgs) {
+
+    // Only benchmark the given hash functions, if any were explicitly specified
+    let hash_functions: Vec<HashFunctionType> = if options.algorithm.is_empty() {
+        HashFunctionType::all().collect()
+    } else {
+        get_hash_functions(options)
+    };
+
+    let results: Vec<BenchmarkResult> = match &args.input {
+        Some(target) => {
+            if let Err(e) = validate_hash_target(target) {
+                error_exit(Some(format!("Invalid benchmark path provided: {e:?}")));
+            }
+
+            print_status(options, &format!("> Benchmarking hash functions on {:?}...\n", target));
+            hash_functions
+                .iter()
+                .map(|hash_function| {
+                    let hash_computer: Arc<HashComputer> = HashComputer::new(
+                        get_hashing_config(options),
+                        *hash_function,
+                        vec![],
+                        get_cancellation_token(),
+                        get_hash_key(options, hash_function)
+                    ).unwrap_or_else(|e| error_exit(Some(e.to_string())));
+
+                    let start: Instant = Instant::now();
+                    let size: u64 = hash_computer
+                        .compute_hash_tree(target.clone())
+                        .unwrap_or_else(|e| exit_on_hashing_error(e))
+                        .size;
+                    create_result(*hash_function, size, start)
+                })
+                .collect()
+        },
+        None => {
+            print_status(options, &format!("> Benchmarking hash functions on {} B of in-memory data...\n", args.size));
+            let mut buffer: Vec<u8> = (0..args.size).map(|i| (i % 251) as u8).collect();
+
+            hash_functions
+                .iter()
+                .map(|hash_function| {
+                    let start: Instant = Instant::now();
+                    HasherFactory::get_instance(hash_function, &get_hashing_config(options).xof).compute_hash(&mut buffer);
+                    create_result(*hash_function, args.size, start)
+                })
+                .collect()
+        }
+    };
+
+    match options.output_format {
+        OutputFormat::TEXT => print_results(&results),
+        OutputFormat::JSON => print_json(&results),
+    }
+}
+
+fn create_result(algorithm: HashFunctionType, bytes: u64, start: Instant) -> BenchmarkResult {
+    let seconds: f64 = start.elapsed().as_secs_f64();
+    let megabytes: f64 = ratio(bytes, mb_to_bytes(1)).unwrap_or(0.0);
+
+    BenchmarkResult {
+        algorithm,
+        bytes,
+        seconds,
+        throughput: if seconds > 0.0 { megabytes / seconds } else { 0.0 }
+    }
+}
+
+fn print_results(results: &[BenchmarkResult]) {
+    println!("{:<12}{:>16}{:>12}{:>14}", "Algorithm", "Bytes", "Seconds", "MB/s");
+
+    for result in results {
+        println!(
+            "{:<12}{:>16}{:>12.3}{:>14.2}",
+            format!("{:?}", result.algorithm), result.bytes, result.seconds, result.throughput
+        );
+    }
+    println!();
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use colored::Colorize;
+use crate::commands::{compute_hash_tree, exit_on_hashing_error, get_cancellation_token, get_hash_key, get_hash_tree_settings, get_hashing_config, load_hash_tree, print_json, print_status};
+use crate::diff::{diff_hash_trees, ChangeKind, TreeChange};
+use crate::hashing::HashComputer;
+use crate::input::cli::{DiffArgs, GlobalOptions, OutputFormat};
+use crate::input::input::{get_hash_function, get_hash_target, validate_hash_target};
+use crate::models::HashedEntry;
+use crate::snapshot::HashTreeSnapshot;
+use crate::util::error_exit;
+
+pub fn run(options: &GlobalOptions, args: &DiffArgs) {
+
+    let baseline: Option<HashTreeSnapshot> = args.tree.as_ref().map(|tree_file| load_hash_tree(options, tree_file));
+
+    let hash_target: PathBuf = get_hash_target(&args.input, options);
+    let (hash_function, hashing_config) = match &baseline {
+        Some(baseline) => get_hash_tree_settings(options, baseline),
+        None => (get_hash_function(options), get_hashing_config(options))
+    };
+
+    let key: Option<Vec<u8>> = get_hash_key(options, &hash_function);
+    let output: HashedEntry = compute_hash_tree(options, hash_target, hash_function, hashing_config.clone());
+
+    let compared_tree: HashedEntry = match (baseline, &args.against) {
+        (Some(baseline), _) => baseline.root,
+        (None, Some(compared_target)) => {
+            if let Err(e) = validate_hash_target(compared_target) {
+                error_exit(Some(format!("Invalid comparison path provided: {e:?}")));
+            }
+
+            print_status(options, &format!("> Computing hash value of {:?} for comparison...\n", compared_target));
+            let hash_computer: Arc<HashComputer> = HashComputer::new(
+                hashing_config.clone(), hash_function, vec![], get_cancellation_token(), key.clone()
+            ).unwrap_or_else(|e| error_exit(Some(e.to_string())));
+            hash_computer.compute_hash_tree(compared_target.clone()).unwrap_or_else(|e| exit_on_hashing_error(e))
+        },
+        (None, None) => error_exit(Some("Neither a target nor a hash tree to compare against was specified".to_string()))
+    };
+
+    let changes: Vec<TreeChange> = diff_hash_trees(
+        &compared_tree, &output, &hash_function, &hashing_config.xof, key.as_deref()
+    ).unwrap_or_else(|e| error_exit(Some(e)));
+
+    match options.output_format {
+        OutputFormat::TEXT => print_tree_changes(&changes),
+        OutputFormat::JSON => print_json(&changes),
+    }
+}
+
+pub fn print_tree_changes(changes: &[TreeChange]) {
+    if changes.is_empty() {
+        println!("{}\n", "No differences found".green().bold());
+        return;
+    }
+
+    for change in changes {
+        let line = format!("{:<18}{:<12}{:>16} B   {}", change.kind, format!("{:?}", change.target_type), change.size, change.path);
+
+        match change.kind {
+            ChangeKind::ADDED => println!("{}", line.green()),
+            ChangeKind::REMOVED => println!("{}", line.red()),
+            ChangeKind::MODIFIED => println!("{}", line.yellow()),
+        }
+
+        for (start, end) in &change.changed_regions {
+            println!("{:<30}{:>16} B   bytes {start}..{end}", "", end - start);
+        }
+    }
+
+    let count = |kind: ChangeKind| changes.iter().filter(|change| change.kind == kind).count();
+    println!(
+        "\n{} added, {} removed, {} modified\n",
+        count(ChangeKind::ADDED), count(ChangeKind::REMOVED), count(ChangeKind::MODIFIED)
+    );
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use colored::Colorize;
+use crate::commands::{exit_on_hashing_error, get_cancellation_token, get_hash_key, get_hashing_config, print_json};
+use crate::dupes::{find_duplicates, DuplicateReport};
+use crate::hashing::HashComputer;
+use crate::input::cli::{DupesArgs, GlobalOptions, OutputFormat};
+use crate::input::input::{get_hash_function, validate_hash_target};
+use crate::models::HashFunctionType;
+use crate::util::error_exit;
+
+pub fn run(options: &GlobalOptions, args: &DupesArgs) {
+    for root in &args.paths {
+        if let Err(e) = validate_hash_target(root) {
+            error_exit(Some(format!("Invalid search path provided: {e:?}")));
+        }
+    }
+
+    let hash_function: HashFunctionType = get_hash_function(options);
+
+    let hash_computer: Arc<HashComputer> = HashComputer::new(
+        get_hashing_config(options), hash_function, vec![], get_cancellation_token(), get_hash_key(options, &hash_function)
+    ).unwrap_or_else(|e| error_exit(Some(e.to_string())));
+    let report: DuplicateReport = find_duplicates(&args.paths, &hash_computer, &hash_function)
+        .unwrap_or_else(|e| exit_on_hashing_error(e));
+
+    match options.output_format {
+        OutputFormat::TEXT => print_duplicate_report(&report),
+        OutputFormat::JSON => print_json(&report),
+    }
+}
+
+fn print_duplicate_report(report: &DuplicateReport) {
+    for set in &report.sets {
+        let header = format!(
+            "{} files of {} B ({} B wasted), hash: {}",
+            set.paths.len(), set.size, set.wasted_bytes, hex::encode(&set.hash)
+        ).yellow().bold();
+        println!("{header}");
+
+        for path in &set.paths {
+            println!("    {}", path.to_string_lossy());
+        }
+        println!();
+    }
+
+    let redundant_files: usize = report.sets.iter().map(|set| set.paths.len() - 1).sum();
+    let summary = format!(
+        "{} sets of duplicates, {} redundant files, {} B wasted",
+        report.sets.len(), redundant_files, report.total_wasted_bytes
+    ).cyan().bold();
+    println!("{summary}\n");
+}
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+use serde::Serialize;
+use crate::commands::{compute_hash_trees, get_hashing_config, print_hash, print_json, print_status};
+use crate::input::cli::{GlobalOptions, HashArgs, OutputFormat};
+use crate::input::input::{get_hash_functions, get_hash_target};
+use crate::models::{HashFunctionType, HashedEntry, HashingConfig};
+use crate::snapshot::{export_hash_tree, HashTreeSnapshot};
+use crate::util::error_exit;
+
+#[derive(Serialize)]
+struct HashOutput {
+    algorithm: HashFunctionType,
+    target: PathBuf,
+    config: HashingConfig,
+
+    #[serde(with = "hex")]
+    hash: Vec<u8>,
+}
+
+pub fn run(options: &GlobalOptions, args: &HashArgs) {
+
+    let hash_target: PathBuf = get_hash_target(&args.input, options);
+    let hash_functions: Vec<HashFunctionType> = get_hash_functions(options);
+    let hashing_config: HashingConfig = get_hashing_config(options);
+
+    // Hash tree files describe the tree of a single hash function
+    if args.export_tree.is_some() && hash_functions.len() > 1 {
+        error_exit(Some("Hash trees can only be exported when computing a single hash function".to_string()));
+    }
+
+    let mut outputs: Vec<HashedEntry> = compute_hash_trees(options, hash_target.clone(), &hash_functions, hashing_config.clone());
+
+    match options.output_format {
+        OutputFormat::TEXT => {
+            for (hash_function, output) in hash_functions.iter().zip(&outputs) {
+                print_hash(hash_function, &output.hash);
+            }
+        },
+        OutputFormat::JSON => {
+            let hash_outputs: Vec<HashOutput> = hash_functions
+                .iter()
+                .zip(&outputs)
+                .map(|(hash_function, output)| HashOutput {
+                    algorithm: *hash_function,
+                    target: hash_target.clone(),
+                    config: hashing_config.clone(),
+                    hash: output.hash.clone()
+                })
+                .collect();
+
+            // A single hash value is still printed as object, so existing consumers keep working
+            match hash_outputs.as_slice() {
+                [hash_output] => print_json(hash_output),
+                _ => print_json(&hash_outputs),
+            }
+        },
+    }
+
+    if let Some(tree_file) = &args.export_tree {
+        let snapshot: HashTreeSnapshot = HashTreeSnapshot::new(hash_functions[0], hashing_config, outputs.swap_remove(0));
+
+        export_hash_tree(tree_file, &snapshot).unwrap_or_else(|e| {
+            error_exit(Some(format!("Unable to export hash tree to '{:?}': {e:?}", tree_file)));
+        });
+        print_status(options, &format!("> Exported hash tree to {:?}", tree_file));
+    }
+}
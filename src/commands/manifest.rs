@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use crate::commands::{compute_hash_tree, get_hashing_config, print_status};
+use crate::input::cli::{GlobalOptions, ManifestArgs, OutputFormat};
+use crate::input::input::{get_hash_function, get_hash_target};
+use crate::models::{HashFunctionType, HashedEntry, HashingConfig, TargetType};
+use crate::snapshot::{export_hash_tree, HashTreeSnapshot};
+use crate::util::error_exit;
+
+pub fn run(options: &GlobalOptions, args: &ManifestArgs) {
+
+    let hash_target: PathBuf = get_hash_target(&args.input, options);
+    let hash_function: HashFunctionType = get_hash_function(options);
+    let hashing_config: HashingConfig = get_hashing_config(options);
+
+    let output: HashedEntry = compute_hash_tree(options, hash_target, hash_function, hashing_config.clone());
+
+    let result = match options.output_format {
+        OutputFormat::TEXT => write_checksum_list(&args.output, &output),
+        OutputFormat::JSON => export_hash_tree(&args.output, &HashTreeSnapshot::new(hash_function, hashing_config, output)),
+    };
+
+    result.unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to write manifest to '{:?}': {e:?}", args.output)));
+    });
+
+    print_status(options, &format!("> Wrote manifest to {:?}", args.output));
+}
+
+/// Write one line per file, consisting of the file's hash value and its path relative to the root
+fn write_checksum_list(path: &PathBuf, root: &HashedEntry) -> std::io::Result<()> {
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+
+    match root.target_type {
+        TargetType::FILE => writeln!(writer, "{}  {}", hex::encode(&root.hash), root.name)?,
+        TargetType::DIRECTORY => write_directory_checksums(&mut writer, root, "")?,
+    }
+
+    writer.flush()
+}
+
+fn write_directory_checksums(writer: &mut BufWriter<File>, directory: &HashedEntry, prefix: &str) -> std::io::Result<()> {
+    for child in &directory.children {
+        let path: String = format!("{prefix}{}", child.name);
+
+        match child.target_type {
+            TargetType::FILE => writeln!(writer, "{}  {path}", hex::encode(&child.hash))?,
+            TargetType::DIRECTORY => write_directory_checksums(writer, child, &format!("{path}/"))?,
+        }
+    }
+
+    Ok(())
+}
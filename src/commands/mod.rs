@@ -0,0 +1,378 @@
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use colored::Colorize;
+use serde::Serialize;
+use crate::cancellation::CancellationToken;
+use crate::checkpoint::{import_checkpoint, Checkpoint, CheckpointWriter};
+use crate::hasher::HasherFactory;
+use crate::hashing::HashComputer;
+use crate::input::cli::{GlobalOptions, OutputFormat, ProgressMode};
+use crate::input::input::get_hash_function;
+use crate::observer::HashObserver;
+use crate::models::{HashFunctionType, HashedEntry, HashingConfig, InternalStateUpdate, XofParameters};
+use crate::progress_tracker::{ProgressStyle, ProgressTracker};
+use crate::snapshot::{import_hash_tree, HashTreeSnapshot};
+use crate::util::size::format_size;
+use crate::util::{error_exit, error_exit_with_code, PAR_HASH_CANCELLED_ERROR_CODE};
+
+pub mod bench;
+pub mod diff;
+pub mod dupes;
+pub mod hash;
+pub mod manifest;
+pub mod verify;
+
+/// Print a status message. If machine-readable output is requested, the message is written to
+/// stderr to keep stdout parsable.
+pub fn print_status(options: &GlobalOptions, msg: &str) {
+    match options.output_format {
+        OutputFormat::TEXT => println!("{msg}"),
+        OutputFormat::JSON => eprintln!("{msg}"),
+    }
+}
+
+pub fn print_json<T: Serialize>(value: &T) {
+    let json: String = serde_json::to_string_pretty(value).unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to serialize output: {e:?}")));
+    });
+
+    println!("{json}");
+}
+
+pub fn print_hash(hash_function: &HashFunctionType, hash: &[u8]) {
+    let encoded_hash_val = format!("{:?}-based hash: {:?}", hash_function, hex::encode(hash)).cyan().bold();
+    println!("\n\n{}\n\n", encoded_hash_val);
+}
+
+pub fn get_hashing_config(options: &GlobalOptions) -> HashingConfig {
+    HashingConfig {
+        split_threshold: options.split_size,
+        chunk_size: options.chunk_size,
+        keyed: is_keyed(options),
+        xof: XofParameters {
+            output_bits: options.output_bits,
+            customization: options.customization.clone()
+        }
+    }
+}
+
+fn is_keyed(options: &GlobalOptions) -> bool {
+    options.key_file.is_some() || options.key_env.is_some()
+}
+
+/// Read the secret key of keyed hash functions and ensure the hash function supports it
+pub fn get_hash_key(options: &GlobalOptions, hash_function: &HashFunctionType) -> Option<Vec<u8>> {
+    let key: Vec<u8> = read_hash_key(options)?;
+
+    if let Err(e) = HasherFactory::get_keyed_instance(hash_function, &key) {
+        error_exit(Some(e));
+    }
+
+    Some(key)
+}
+
+fn read_hash_key(options: &GlobalOptions) -> Option<Vec<u8>> {
+    let key: Vec<u8> = match (&options.key_file, &options.key_env) {
+        (Some(key_file), _) => fs::read(key_file).unwrap_or_else(|e| {
+            error_exit(Some(format!("Unable to read key from '{:?}': {e:?}", key_file)));
+        }),
+        (None, Some(variable)) => env::var_os(variable)
+            .map(|value| value.into_encoded_bytes())
+            .unwrap_or_else(|| error_exit(Some(format!("Environment variable '{variable}' containing the key is not set")))),
+        (None, None) => return None
+    };
+
+    if key.is_empty() {
+        error_exit(Some("The key must not be empty".to_string()));
+    }
+
+    Some(key)
+}
+
+/// Human-readable description of the effective split threshold and chunk size
+pub fn describe_hashing_config(config: &HashingConfig) -> String {
+    let Some(split_threshold) = config.split_threshold else {
+        return String::from("files are hashed as a whole");
+    };
+
+    let chunk_size: String = match config.chunk_size {
+        Some(chunk_size) => format!("chunks of {}", describe_size(chunk_size)),
+        None => String::from("chunks whose size depends on the file size"),
+    };
+
+    format!("files of at least {} are split into {chunk_size}", describe_size(split_threshold))
+}
+
+fn describe_size(bytes: u64) -> String {
+    match format_size(bytes) {
+        size if size.ends_with(" B") => size,
+        size => format!("{size} ({bytes} B)")
+    }
+}
+
+pub fn load_hash_tree(options: &GlobalOptions, tree_file: &PathBuf) -> HashTreeSnapshot {
+    print_status(options, &format!("> Loading hash tree from {:?}...", tree_file));
+
+    let snapshot: HashTreeSnapshot = import_hash_tree(tree_file, read_hash_key(options).as_deref()).unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to import hash tree from '{:?}': {e:?}", tree_file)));
+    });
+
+    print_status(options, &format!("> Successfully validated hash tree of '{}'", snapshot.root.name));
+    snapshot
+}
+
+/// When comparing against a saved hash tree, the input has to be hashed exactly like the tree was.
+/// Settings that are not explicitly specified are therefore taken from the tree.
+pub fn get_hash_tree_settings(options: &GlobalOptions, baseline: &HashTreeSnapshot) -> (HashFunctionType, HashingConfig) {
+
+    let hash_function: HashFunctionType = if options.algorithm.is_empty() {
+        baseline.algorithm
+    } else {
+        get_hash_function(options)
+    };
+
+    if hash_function != baseline.algorithm {
+        error_exit(Some(format!(
+            "Hash function {:?} differs from the hash function {:?} the hash tree was computed with",
+            hash_function, baseline.algorithm
+        )));
+    }
+
+    let split_size_differs = options.split_size.is_some() && options.split_size != baseline.config.split_threshold;
+    let chunk_size_differs = options.chunk_size.is_some() && options.chunk_size != baseline.config.chunk_size;
+    let output_bits_differ = options.output_bits.is_some() && options.output_bits != baseline.config.xof.output_bits;
+    let customization_differs = options.customization.is_some() && options.customization != baseline.config.xof.customization;
+
+    if baseline.config.keyed != is_keyed(options) {
+        let keyed: &str = if baseline.config.keyed { "with" } else { "without" };
+        error_exit(Some(format!("The hash tree was computed {keyed} a key, which has to be used for comparisons as well")));
+    }
+
+    if split_size_differs || chunk_size_differs || output_bits_differ || customization_differs {
+        error_exit(Some(format!(
+            "Split threshold, chunk size, output size and customization string have to match the hash tree's settings: {:?}",
+            baseline.config
+        )));
+    }
+
+    (hash_function, baseline.config.clone())
+}
+
+/// Compute the hash tree of the given target while displaying the progress, if enabled
+pub fn compute_hash_tree(
+    options: &GlobalOptions,
+    target: PathBuf,
+    hash_function: HashFunctionType,
+    hashing_config: HashingConfig
+) -> HashedEntry {
+    compute_hash_trees(options, target, &[hash_function], hashing_config).swap_remove(0)
+}
+
+/// Compute the hash trees of all given hash functions in a single pass over the target
+pub fn compute_hash_trees(
+    options: &GlobalOptions,
+    target: PathBuf,
+    hash_functions: &[HashFunctionType],
+    hashing_config: HashingConfig
+) -> Vec<HashedEntry> {
+
+    // Checkpoints store the hash values of a single hash function
+    if hash_functions.len() > 1 && (options.checkpoint.is_some() || options.resume.is_some()) {
+        error_exit(Some("Checkpoints are only supported when computing a single hash function".to_string()));
+    }
+
+    let hash_function: HashFunctionType = hash_functions[0];
+    let names: Vec<String> = hash_functions.iter().map(|hash_function| format!("{:?}", hash_function)).collect();
+
+    let input = format!("Computing {}-based hash value for {:?}", names.join(", "), target).magenta().bold();
+    print_status(options, &format!("\n{input}\n"));
+    print_status(options, &format!("> Settings: {}", describe_hashing_config(&hashing_config)));
+
+    // Messaging channel to update the internal state and total progress
+    let (tx, rx) = get_messaging_channel(options);
+
+    let progress_tracker: Option<JoinHandle<()>> = init_progress_tracker(options, &target, rx);
+
+    let mut observers: Vec<Box<dyn HashObserver>> = match tx {
+        Some(tx) => vec![Box::new(tx)],
+        None => vec![]
+    };
+
+    let key: Option<Vec<u8>> = get_hash_key(options, &hash_function);
+
+    // The key is shared, so every hash function has to support it
+    if let Some(key) = &key {
+        for hash_function in &hash_functions[1..] {
+            if let Err(e) = HasherFactory::get_keyed_instance(hash_function, key) {
+                error_exit(Some(e));
+            }
+        }
+    }
+    let resumed_checkpoint: Option<Checkpoint> = options.resume.as_ref().map(|file| load_checkpoint(options, file, &target));
+
+    if let Some(checkpoint_file) = options.checkpoint.as_ref().or(options.resume.as_ref()) {
+        let checkpoint: Checkpoint = resumed_checkpoint
+            .clone()
+            .unwrap_or_else(|| {
+                Checkpoint::new(hash_function, hashing_config.clone(), target.clone(), key.as_deref())
+                    .unwrap_or_else(|e| error_exit(Some(e)))
+            });
+
+        let interval: Duration = Duration::from_secs(options.checkpoint_interval);
+        observers.push(Box::new(CheckpointWriter::new(checkpoint_file.clone(), interval, checkpoint)));
+    }
+
+    let hash_computer: Arc<HashComputer> = match resumed_checkpoint {
+        Some(checkpoint) => {
+            HashComputer::resume(hashing_config, hash_function, observers, get_cancellation_token(), key, checkpoint)
+                .unwrap_or_else(|e| error_exit(Some(format!("Unable to resume from checkpoint: {e:?}"))))
+        },
+        None => HashComputer::with_algorithms(
+            hashing_config, hash_functions.to_vec(), observers, get_cancellation_token(), key
+        ).unwrap_or_else(|e| error_exit(Some(e.to_string())))
+    };
+    print_status(options, "> Starting to compute hash value...\n");
+    let output: std::io::Result<Vec<HashedEntry>> = hash_computer.compute_hash_trees(target);
+    drop(hash_computer);
+
+    // Terminate progress tracker thread
+    if let Some(progress_tracker) = progress_tracker {
+        progress_tracker.join().unwrap_or_else(|_| {
+            eprintln!("An error occurred while waiting for the progress tracker thread to terminate!");
+        });
+    }
+
+    output.unwrap_or_else(|e| exit_on_hashing_error(e))
+}
+
+fn load_checkpoint(options: &GlobalOptions, checkpoint_file: &PathBuf, target: &PathBuf) -> Checkpoint {
+    print_status(options, &format!("> Loading checkpoint from {:?}...", checkpoint_file));
+
+    let checkpoint: Checkpoint = import_checkpoint(checkpoint_file).unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to import checkpoint from '{:?}': {e:?}", checkpoint_file)));
+    });
+
+    // Directory hash values include their paths, so the target has to be specified identically
+    if checkpoint.target.as_os_str() != target.as_os_str() {
+        error_exit(Some(format!("Checkpoint was created for target {:?} instead of {:?}", checkpoint.target, target)));
+    }
+
+    print_status(options, &format!("> Resuming with {} already hashed files", checkpoint.files.len()));
+    checkpoint
+}
+
+/// Token that is cancelled once SIGINT or SIGTERM is received, so running computations can stop
+/// cleanly. A second signal terminates the process immediately.
+pub fn get_cancellation_token() -> CancellationToken {
+    static CANCELLATION_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+    CANCELLATION_TOKEN.get_or_init(|| {
+        let token: CancellationToken = CancellationToken::new();
+        let handler_token: CancellationToken = token.clone();
+
+        ctrlc::set_handler(move || {
+            if handler_token.is_cancelled() {
+                process::exit(PAR_HASH_CANCELLED_ERROR_CODE);
+            }
+            handler_token.cancel();
+        }).unwrap_or_else(|e| {
+            error_exit(Some(format!("Unable to install signal handler: {e:?}")));
+        });
+
+        token
+    }).clone()
+}
+
+pub fn exit_on_hashing_error(error: Error) -> ! {
+    match error.kind() {
+        ErrorKind::Interrupted => error_exit_with_code(PAR_HASH_CANCELLED_ERROR_CODE, Some(String::from("\nCancelled!"))),
+        _ => error_exit(Some(format!("Unable to compute hash value: {error}")))
+    }
+}
+
+fn get_progress_style(options: &GlobalOptions) -> Option<ProgressStyle> {
+    if options.no_progress {
+        return None;
+    }
+
+    match options.progress {
+        ProgressMode::NONE => None,
+        ProgressMode::TERMINAL => Some(ProgressStyle::TERMINAL),
+        ProgressMode::PLAIN => Some(ProgressStyle::PLAIN),
+        ProgressMode::JSON => Some(ProgressStyle::JSON),
+
+        // The overview is redrawn on stdout and thus only shown for human-readable output
+        ProgressMode::AUTO if options.output_format == OutputFormat::TEXT && std::io::stdout().is_terminal() => {
+            Some(ProgressStyle::TERMINAL)
+        },
+        ProgressMode::AUTO => Some(ProgressStyle::PLAIN),
+    }
+}
+
+#[cfg(unix)]
+fn get_progress_sink(options: &GlobalOptions) -> Box<dyn Write + Send> {
+    use std::fs::File;
+    use std::os::fd::{BorrowedFd, OwnedFd};
+
+    let fd: i32 = match options.progress_fd {
+        Some(2) | None => return Box::new(std::io::stderr()),
+        Some(fd) => fd,
+    };
+
+    // SAFETY: fcntl only queries the status flags of the descriptor and fails with EBADF instead
+    // of touching anything if it is not open
+    let flags: i32 = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        error_exit(Some(format!("Invalid progress file descriptor {fd}: {}", std::io::Error::last_os_error())));
+    }
+    if flags & libc::O_ACCMODE == libc::O_RDONLY {
+        error_exit(Some(format!("Progress file descriptor {fd} is not open for writing")));
+    }
+
+    // SAFETY: fcntl succeeded above, so the descriptor is open. It is only borrowed to duplicate it
+    // and the duplicate is owned by the sink, so the caller's descriptor is never closed by
+    // par-hash. No other code of par-hash closes descriptors it did not open itself, so it stays
+    // open for the duration of the borrow.
+    let borrowed_fd: BorrowedFd = unsafe { BorrowedFd::borrow_raw(fd) };
+    let owned_fd: OwnedFd = borrowed_fd.try_clone_to_owned().unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to duplicate progress file descriptor {fd}: {e}")));
+    });
+
+    Box::new(File::from(owned_fd))
+}
+
+#[cfg(not(unix))]
+fn get_progress_sink(_options: &GlobalOptions) -> Box<dyn Write + Send> {
+    Box::new(std::io::stderr())
+}
+
+fn get_messaging_channel(options: &GlobalOptions) -> (Option<Sender<InternalStateUpdate>>, Option<Receiver<InternalStateUpdate>>) {
+    if get_progress_style(options).is_some() {
+        let (tx, rx) = mpsc::channel();
+        (Some(tx), Some(rx))
+    } else {
+        (None, None)
+    }
+}
+
+fn init_progress_tracker(options: &GlobalOptions, target: &Path, rx: Option<Receiver<InternalStateUpdate>>) -> Option<JoinHandle<()>> {
+    let style: ProgressStyle = get_progress_style(options)?;
+
+    print_status(options, "> Initializing progress tracker...");
+    let progress_tracker: ProgressTracker = ProgressTracker::init(target, style, get_progress_sink(options), !options.no_totals);
+
+    let thread = thread::spawn(move || {
+        progress_tracker.track_progress(rx.unwrap());
+    });
+
+    print_status(options, "> Completed progress tracker initialization!");
+    Some(thread)
+}
@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+use colored::Colorize;
+use serde::Serialize;
+use crate::commands::diff::print_tree_changes;
+use crate::commands::{compute_hash_tree, get_hash_key, get_hash_tree_settings, get_hashing_config, load_hash_tree, print_hash, print_json};
+use crate::diff::{diff_hash_trees, TreeChange};
+use crate::input::cli::{GlobalOptions, OutputFormat, VerifyArgs};
+use crate::input::input::{get_hash_function, get_hash_target};
+use crate::models::{HashFunctionType, HashedEntry};
+use crate::snapshot::HashTreeSnapshot;
+use crate::util::error_exit;
+
+#[derive(Serialize)]
+struct VerificationOutput {
+    algorithm: HashFunctionType,
+    target: Option<PathBuf>,
+    verified: bool,
+
+    #[serde(with = "hex")]
+    expected: Vec<u8>,
+
+    #[serde(with = "hex")]
+    actual: Vec<u8>,
+
+    /// Differences to the hash tree the target was verified against
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<TreeChange>,
+}
+
+pub fn run(options: &GlobalOptions, args: &VerifyArgs) {
+
+    let baseline: Option<HashTreeSnapshot> = args.tree.as_ref().map(|tree_file| load_hash_tree(options, tree_file));
+
+    // Without a target, only the integrity of the hash tree itself is verified
+    if let (Some(baseline), None) = (&baseline, &args.input) {
+        report_verification(options, VerificationOutput {
+            algorithm: baseline.algorithm,
+            target: None,
+            verified: true,
+            expected: baseline.get_root_hash().clone(),
+            actual: baseline.get_root_hash().clone(),
+            changes: vec![]
+        });
+        return;
+    }
+
+    let hash_target: PathBuf = get_hash_target(&args.input, options);
+
+    let output: VerificationOutput = match baseline {
+        Some(baseline) => {
+            let (hash_function, hashing_config) = get_hash_tree_settings(options, &baseline);
+            let key: Option<Vec<u8>> = get_hash_key(options, &hash_function);
+            let actual: HashedEntry = compute_hash_tree(options, hash_target.clone(), hash_function, hashing_config.clone());
+
+            VerificationOutput {
+                algorithm: hash_function,
+                target: Some(hash_target),
+                verified: actual.hash == baseline.root.hash,
+                changes: diff_hash_trees(&baseline.root, &actual, &hash_function, &hashing_config.xof, key.as_deref())
+                    .unwrap_or_else(|e| error_exit(Some(e))),
+                expected: baseline.root.hash,
+                actual: actual.hash,
+            }
+        },
+        None => {
+            let expected: Vec<u8> = decode_expected_hash(args.expected.as_ref().unwrap());
+            let hash_function: HashFunctionType = get_hash_function(options);
+            let actual: HashedEntry = compute_hash_tree(options, hash_target.clone(), hash_function, get_hashing_config(options));
+
+            VerificationOutput {
+                algorithm: hash_function,
+                target: Some(hash_target),
+                verified: actual.hash == expected,
+                expected,
+                actual: actual.hash,
+                changes: vec![]
+            }
+        }
+    };
+
+    let verified: bool = output.verified;
+    report_verification(options, output);
+
+    if !verified {
+        error_exit(None);
+    }
+}
+
+fn decode_expected_hash(expected: &str) -> Vec<u8> {
+    hex::decode(expected.trim()).unwrap_or_else(|e| {
+        error_exit(Some(format!("Expected hash value '{expected}' is not hex-encoded: {e:?}")));
+    })
+}
+
+fn report_verification(options: &GlobalOptions, output: VerificationOutput) {
+    if options.output_format == OutputFormat::JSON {
+        print_json(&output);
+        return;
+    }
+
+    print_hash(&output.algorithm, &output.actual);
+
+    if output.verified {
+        println!("{}\n", "Verification succeeded: hash values match".green().bold());
+        return;
+    }
+
+    if !output.changes.is_empty() {
+        print_tree_changes(&output.changes);
+    }
+
+    println!("{}", format!("Verification failed: expected hash {}", hex::encode(&output.expected)).red().bold());
+}
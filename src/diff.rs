@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::fmt;
+use rayon::prelude::*;
+use serde::Serialize;
+use crate::merkle_tree::MerkleTree;
+use crate::models::{HashFunctionType, HashedChunk, HashedEntry, TargetType, XofParameters};
+use crate::snapshot::get_merkle_tree;
+use crate::util::fs::join_relative_path;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ChangeKind {
+    ADDED,
+    REMOVED,
+    MODIFIED,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChangeKind::ADDED => "added",
+            ChangeKind::REMOVED => "removed",
+            ChangeKind::MODIFIED => "modified",
+        };
+        f.pad(name)
+    }
+}
+
+/// Single difference between two hash trees
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeChange {
+    pub kind: ChangeKind,
+    pub target_type: TargetType,
+    pub path: String, // relative to the compared roots
+    pub size: u64, // in bytes, size of the entry in the newer tree (older tree if removed)
+
+    /// Byte ranges (start inclusive, end exclusive) of a modified file that differ, if both versions
+    /// of the file were hashed in chunks
+    pub changed_regions: Vec<(u64, u64)>,
+}
+
+/// Compare two hash trees top-down and report how `new` differs from `old`. Subtrees whose hash
+/// values match are skipped entirely, so the cost is proportional to the number of changes rather
+/// than to the number of entries. Fails if the hash function does not support the key.
+pub fn diff_hash_trees(
+    old: &HashedEntry,
+    new: &HashedEntry,
+    algorithm: &HashFunctionType,
+    xof: &XofParameters,
+    key: Option<&[u8]>
+) -> Result<Vec<TreeChange>, String> {
+    let mut changes: Vec<TreeChange> = diff_entries(old, new, "", algorithm, xof, key)?;
+    changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.kind.cmp(&b.kind)));
+    Ok(changes)
+}
+
+fn diff_entries(
+    old: &HashedEntry,
+    new: &HashedEntry,
+    path: &str,
+    algorithm: &HashFunctionType,
+    xof: &XofParameters,
+    key: Option<&[u8]>
+) -> Result<Vec<TreeChange>, String> {
+
+    if old.hash == new.hash {
+        return Ok(vec![]);
+    }
+
+    if old.target_type != new.target_type {
+        return Ok(vec![
+            create_change(ChangeKind::REMOVED, old, path),
+            create_change(ChangeKind::ADDED, new, path),
+        ]);
+    }
+
+    if new.target_type == TargetType::FILE {
+        let mut change: TreeChange = create_change(ChangeKind::MODIFIED, new, path);
+        change.changed_regions = get_changed_regions(old, new, algorithm, xof, key)?;
+        return Ok(vec![change]);
+    }
+
+    let mut changes: Vec<TreeChange> = vec![];
+
+    let old_children: HashMap<&str, &HashedEntry> = old.children
+        .iter()
+        .map(|child| (child.name.as_str(), child))
+        .collect();
+    let new_children: HashMap<&str, &HashedEntry> = new.children
+        .iter()
+        .map(|child| (child.name.as_str(), child))
+        .collect();
+
+    for old_child in &old.children {
+        if !new_children.contains_key(old_child.name.as_str()) {
+            changes.push(create_change(ChangeKind::REMOVED, old_child, &join_relative_path(path, &old_child.name)));
+        }
+    }
+
+    for new_child in &new.children {
+        if !old_children.contains_key(new_child.name.as_str()) {
+            changes.push(create_change(ChangeKind::ADDED, new_child, &join_relative_path(path, &new_child.name)));
+        }
+    }
+
+    // Concurrently descend into all entries present in both trees
+    let nested_changes: Vec<Vec<TreeChange>> = new.children
+        .par_iter()
+        .filter_map(|new_child| {
+            old_children
+                .get(new_child.name.as_str())
+                .map(|old_child| diff_entries(old_child, new_child, &join_relative_path(path, &new_child.name), algorithm, xof, key))
+        })
+        .collect::<Result<Vec<Vec<TreeChange>>, String>>()?;
+
+    changes.extend(nested_changes.into_iter().flatten());
+    Ok(changes)
+}
+
+fn create_change(kind: ChangeKind, entry: &HashedEntry, path: &str) -> TreeChange {
+    TreeChange {
+        kind,
+        target_type: entry.target_type,
+        path: if path.is_empty() { String::from(".") } else { path.to_string() },
+        size: entry.size,
+        changed_regions: vec![]
+    }
+}
+
+/// Determine which byte ranges of a modified file differ by comparing the hashes of its chunks
+fn get_changed_regions(
+    old: &HashedEntry,
+    new: &HashedEntry,
+    algorithm: &HashFunctionType,
+    xof: &XofParameters,
+    key: Option<&[u8]>
+) -> Result<Vec<(u64, u64)>, String> {
+
+    // Without chunk hashes on both sides, the file can only be compared as a whole
+    if old.chunks.is_empty() || new.chunks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let same_layout: bool = old.chunks.len() == new.chunks.len() && old.chunks
+        .iter()
+        .zip(&new.chunks)
+        .all(|(old_chunk, new_chunk)| old_chunk.offset == new_chunk.offset);
+
+    let differing_chunks: Vec<(u64, u64)> = if same_layout {
+        let old_tree: MerkleTree = get_merkle_tree(old, algorithm, xof, key)?.unwrap();
+        let new_tree: MerkleTree = get_merkle_tree(new, algorithm, xof, key)?.unwrap();
+
+        new_tree
+            .get_differing_leaves(&old_tree)
+            .into_iter()
+            .map(|index| get_chunk_range(&new.chunks[index]))
+            .collect()
+    } else {
+        get_differing_chunks_by_offset(&old.chunks, &new.chunks)
+    };
+
+    Ok(merge_adjacent_ranges(differing_chunks))
+}
+
+/// Fallback for files that were split differently, e.g. because their size changed. Chunks are
+/// matched by their offset and every chunk without an identical counterpart is considered changed.
+fn get_differing_chunks_by_offset(old: &[HashedChunk], new: &[HashedChunk]) -> Vec<(u64, u64)> {
+    let old_chunks: HashMap<(u64, u64), &Vec<u8>> = old
+        .iter()
+        .map(|chunk| ((chunk.offset, chunk.length), &chunk.hash))
+        .collect();
+
+    let mut differing_chunks: Vec<(u64, u64)> = new
+        .iter()
+        .filter(|chunk| old_chunks.get(&(chunk.offset, chunk.length)) != Some(&&chunk.hash))
+        .map(get_chunk_range)
+        .collect();
+
+    // Data that was cut off from the end of the file
+    let new_size: u64 = new.last().map(|chunk| chunk.offset + chunk.length).unwrap_or(0);
+    let old_size: u64 = old.last().map(|chunk| chunk.offset + chunk.length).unwrap_or(0);
+
+    if old_size > new_size {
+        differing_chunks.push((new_size, old_size));
+    }
+
+    differing_chunks
+}
+
+fn get_chunk_range(chunk: &HashedChunk) -> (u64, u64) {
+    (chunk.offset, chunk.offset + chunk.length)
+}
+
+fn merge_adjacent_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort();
+
+    let mut merged: Vec<(u64, u64)> = vec![];
+
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end))
+        }
+    }
+
+    merged
+}
+
+#[cfg(all(test, feature = "sha2"))]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use crate::cancellation::CancellationToken;
+    use crate::hashing::HashComputer;
+    use crate::models::{HashFunctionType, HashedEntry, HashingConfig};
+    use super::*;
+
+    fn create_tree(root: &Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            let path: PathBuf = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    fn hash_tree(target: &Path) -> HashedEntry {
+        let config: HashingConfig = HashingConfig { split_threshold: None, chunk_size: None, keyed: false, xof: XofParameters::default() };
+        HashComputer::new(config, HashFunctionType::SHA2_256, vec![], CancellationToken::new(), None)
+            .unwrap()
+            .compute_hash_tree(target.to_path_buf())
+            .unwrap()
+    }
+
+    #[test]
+    fn identical_subdirectories_under_different_roots_are_unchanged() {
+        let base: PathBuf = std::env::temp_dir().join(format!("par-hash-diff-{}", std::process::id()));
+        let old_root: PathBuf = base.join("old");
+        let new_root: PathBuf = base.join("new");
+        create_tree(&old_root, &[("sub/a", "same"), ("sub/nested/b", "same"), ("c", "old")]);
+        create_tree(&new_root, &[("sub/a", "same"), ("sub/nested/b", "same"), ("c", "new")]);
+
+        let changes: Vec<TreeChange> = diff_hash_trees(
+            &hash_tree(&old_root), &hash_tree(&new_root), &HashFunctionType::SHA2_256, &XofParameters::default(), None
+        ).unwrap();
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::MODIFIED);
+        assert_eq!(changes[0].path, "c");
+    }
+}
@@ -0,0 +1,233 @@
+use std::cmp::min;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Error};
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+use serde::Serialize;
+use crate::hasher::HasherFactory;
+use crate::hashing::HashComputer;
+use crate::models::{HashFunctionType, XofParameters};
+use crate::util::fs::{is_supported_filetype, read_chunk};
+
+/// Number of leading bytes hashed to cheaply rule out files of equal size before hashing them fully
+const PARTIAL_HASH_SIZE: u64 = 4096;
+
+/// Files with identical content
+#[derive(Debug, Serialize)]
+pub struct DuplicateSet {
+    pub size: u64, // in bytes, size of each file in the set
+    pub wasted_bytes: u64, // in bytes, space that could be freed by keeping only one of the files
+
+    #[serde(with = "hex")]
+    pub hash: Vec<u8>,
+
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateReport {
+    pub algorithm: HashFunctionType,
+    pub roots: Vec<PathBuf>,
+    pub total_wasted_bytes: u64,
+    pub sets: Vec<DuplicateSet>,
+}
+
+/// Find all sets of files with identical content below the given roots. Files are first grouped by
+/// size, then by the hash of their first bytes and only the remaining candidates are hashed fully.
+pub fn find_duplicates(
+    roots: &[PathBuf],
+    hash_computer: &HashComputer,
+    algorithm: &HashFunctionType
+) -> std::io::Result<DuplicateReport> {
+
+    let mut files: Vec<(PathBuf, u64)> = roots
+        .par_iter()
+        .map(collect_files)
+        .collect::<std::io::Result<Vec<Vec<(PathBuf, u64)>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    // Overlapping roots must not cause a file to be reported as a duplicate of itself
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    files.retain(|(path, _)| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())));
+
+    let size_groups: Vec<(u64, Vec<PathBuf>)> = group_candidates(
+        files
+            .into_iter()
+            .filter(|(_, size)| *size > 0) // Empty files do not waste any space
+            .map(|(path, size)| (size, path))
+            .collect()
+    );
+
+    let partial_hash_groups: Vec<(u64, Vec<PathBuf>)> = size_groups
+        .into_par_iter()
+        .map(|(size, group)| {
+            let candidates: Vec<(Vec<u8>, PathBuf)> = group
+                .into_par_iter()
+                .map(|path| compute_partial_hash(&path, size, algorithm).map(|hash| (hash, path)))
+                .collect::<std::io::Result<Vec<(Vec<u8>, PathBuf)>>>()?;
+
+            Ok(group_candidates(candidates)
+                .into_iter()
+                .map(|(_, paths)| (size, paths))
+                .collect::<Vec<(u64, Vec<PathBuf>)>>())
+        })
+        .collect::<std::io::Result<Vec<Vec<(u64, Vec<PathBuf>)>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let full_hash_groups: Vec<Vec<DuplicateSet>> = partial_hash_groups
+        .into_par_iter()
+        .map(|(size, group)| {
+            let candidates: Vec<(Vec<u8>, PathBuf)> = group
+                .into_par_iter()
+                .map(|path| hash_computer.compute_hash_tree(path.clone()).map(|entry| (entry.hash, path)))
+                .collect::<std::io::Result<Vec<(Vec<u8>, PathBuf)>>>()?;
+
+            let mut hash_groups: Vec<(Vec<u8>, Vec<PathBuf>)> = group_candidates(candidates);
+
+            // Collisions of non-cryptographic hash functions are easily constructed, so their matches
+            // are only considered identical once the contents were compared
+            if !algorithm.is_cryptographic() {
+                hash_groups = hash_groups
+                    .into_iter()
+                    .map(|(hash, paths)| {
+                        split_by_content(paths).map(|groups| groups.into_iter().map(|paths| (hash.clone(), paths)).collect())
+                    })
+                    .collect::<std::io::Result<Vec<Vec<(Vec<u8>, Vec<PathBuf>)>>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect();
+            }
+
+            let sets: Vec<DuplicateSet> = hash_groups
+                .into_iter()
+                .map(|(hash, mut paths)| {
+                    paths.sort();
+
+                    DuplicateSet {
+                        size,
+                        wasted_bytes: size * (paths.len() as u64 - 1),
+                        hash,
+                        paths
+                    }
+                })
+                .collect();
+
+            Ok(sets)
+        })
+        .collect::<std::io::Result<Vec<Vec<DuplicateSet>>>>()?;
+
+    let mut sets: Vec<DuplicateSet> = full_hash_groups.into_iter().flatten().collect();
+    sets.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes).then(a.paths.cmp(&b.paths)));
+
+    Ok(DuplicateReport {
+        algorithm: *algorithm,
+        roots: roots.to_vec(),
+        total_wasted_bytes: sets.iter().map(|set| set.wasted_bytes).sum(),
+        sets
+    })
+}
+
+/// Group paths by the given key, dropping all groups that consist of a single path
+fn group_candidates<K: Eq + std::hash::Hash>(candidates: Vec<(K, PathBuf)>) -> Vec<(K, Vec<PathBuf>)> {
+    let mut groups: HashMap<K, Vec<PathBuf>> = HashMap::new();
+
+    for (key, path) in candidates {
+        groups.entry(key).or_default().push(path);
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .collect()
+}
+
+/// Split files of equal size into groups of identical content, dropping all files without a
+/// duplicate
+fn split_by_content(mut paths: Vec<PathBuf>) -> std::io::Result<Vec<Vec<PathBuf>>> {
+    let mut groups: Vec<Vec<PathBuf>> = vec![];
+
+    while let Some(reference) = paths.pop() {
+        let mut group: Vec<PathBuf> = vec![];
+        let mut remaining: Vec<PathBuf> = vec![];
+
+        for path in paths {
+            if have_equal_content(&reference, &path)
+                .map_err(|e| add_path_to_error(&path, "Unable to compare file", e))? {
+                group.push(path);
+            } else {
+                remaining.push(path);
+            }
+        }
+
+        if !group.is_empty() {
+            group.push(reference);
+            groups.push(group);
+        }
+        paths = remaining;
+    }
+
+    Ok(groups)
+}
+
+fn have_equal_content(a: &PathBuf, b: &PathBuf) -> std::io::Result<bool> {
+    let mut reader_a: BufReader<File> = BufReader::new(File::open(a)?);
+    let mut reader_b: BufReader<File> = BufReader::new(File::open(b)?);
+
+    loop {
+        let buffer_a: &[u8] = reader_a.fill_buf()?;
+        let buffer_b: &[u8] = reader_b.fill_buf()?;
+
+        if buffer_a.is_empty() || buffer_b.is_empty() {
+            return Ok(buffer_a.is_empty() && buffer_b.is_empty());
+        }
+
+        let length: usize = min(buffer_a.len(), buffer_b.len());
+        if buffer_a[..length] != buffer_b[..length] {
+            return Ok(false);
+        }
+
+        reader_a.consume(length);
+        reader_b.consume(length);
+    }
+}
+
+fn compute_partial_hash(path: &PathBuf, size: u64, algorithm: &HashFunctionType) -> std::io::Result<Vec<u8>> {
+    let mut chunk: Vec<u8> = read_chunk(path, 0, min(size, PARTIAL_HASH_SIZE))
+        .map_err(|e| add_path_to_error(path, "Unable to read from file", e))?;
+
+    // Partial hashes only preselect candidates, so the default output size of XOFs suffices
+    Ok(HasherFactory::get_instance(algorithm, &XofParameters::default()).compute_hash(&mut chunk))
+}
+
+fn collect_files(path: &PathBuf) -> std::io::Result<Vec<(PathBuf, u64)>> {
+
+    if path.is_file() {
+        let metadata: Metadata = path.metadata().map_err(|e| add_path_to_error(path, "Unable to get metadata", e))?;
+        return Ok(vec![(path.clone(), metadata.len())]);
+    }
+
+    let entries: Vec<PathBuf> = fs::read_dir(path)
+        .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<std::io::Result<Vec<PathBuf>>>())
+        .map_err(|e| add_path_to_error(path, "Unable to read directory", e))?
+        .into_iter()
+        .filter(|entry| is_supported_filetype(entry))
+        .collect();
+
+    Ok(entries
+        .par_iter()
+        .map(collect_files)
+        .collect::<std::io::Result<Vec<Vec<(PathBuf, u64)>>>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+fn add_path_to_error(path: &Path, msg: &str, error: Error) -> Error {
+    Error::new(error.kind(), format!("{msg} '{}': {error}", path.display()))
+}
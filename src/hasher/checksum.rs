@@ -0,0 +1,73 @@
+#[cfg(feature = "xxh3")]
+use xxhash_rust::xxh3::Xxh3Default;
+use crate::hasher::Hasher;
+use crate::util::error_exit;
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+enum ChecksumState {
+    #[cfg(feature = "xxh3")]
+    XXH3_64(Box<Xxh3Default>),
+    #[cfg(feature = "xxh3")]
+    XXH3_128(Box<Xxh3Default>),
+    #[cfg(feature = "crc32c")]
+    CRC32C(u32),
+}
+
+/// Adapts non-cryptographic checksums, which are considerably faster than cryptographic hash
+/// functions but do not protect against deliberate modifications. Digests are big-endian, matching
+/// the output of tools like xxhsum.
+pub struct ChecksumHasher {
+    state: Option<ChecksumState>,
+}
+
+impl ChecksumHasher {
+
+    #[cfg(feature = "xxh3")]
+    pub fn xxh3_64() -> Box<dyn Hasher> {
+        Box::new(Self { state: Some(ChecksumState::XXH3_64(Box::new(Xxh3Default::new()))) })
+    }
+
+    #[cfg(feature = "xxh3")]
+    pub fn xxh3_128() -> Box<dyn Hasher> {
+        Box::new(Self { state: Some(ChecksumState::XXH3_128(Box::new(Xxh3Default::new()))) })
+    }
+
+    #[cfg(feature = "crc32c")]
+    pub fn crc32c() -> Box<dyn Hasher> {
+        Box::new(Self { state: Some(ChecksumState::CRC32C(0)) })
+    }
+}
+
+impl Hasher for ChecksumHasher {
+    fn update(&mut self, buffer: &mut [u8]) {
+        match &mut self.state {
+            #[cfg(feature = "xxh3")]
+            Some(ChecksumState::XXH3_64(hasher)) | Some(ChecksumState::XXH3_128(hasher)) => hasher.update(buffer),
+            #[cfg(feature = "crc32c")]
+            Some(ChecksumState::CRC32C(crc)) => *crc = crc32c::crc32c_append(*crc, buffer),
+            None => {}
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<u8> {
+        let state = self.state.take().unwrap_or_else(|| {
+            error_exit(Some("Hasher already finalized".to_string()));
+        });
+
+        match state {
+            #[cfg(feature = "xxh3")]
+            ChecksumState::XXH3_64(hasher) => hasher.digest().to_be_bytes().to_vec(),
+            #[cfg(feature = "xxh3")]
+            ChecksumState::XXH3_128(hasher) => hasher.digest128().to_be_bytes().to_vec(),
+            #[cfg(feature = "crc32c")]
+            ChecksumState::CRC32C(crc) => crc.to_be_bytes().to_vec(),
+        }
+    }
+
+    fn compute_hash(&mut self, buffer: &mut Vec<u8>) -> Vec<u8> {
+        for chunk in buffer.chunks_mut(8192) {
+            self.update(chunk);
+        }
+        self.finalize()
+    }
+}
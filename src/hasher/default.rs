@@ -7,6 +7,7 @@ pub struct GenericHasher<D: Digest + 'static> {
 }
 
 impl <D: Digest + 'static> GenericHasher<D> {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> Box<dyn Hasher> {
         Box::new(Self {
             internal_hasher: Some(D::new())
@@ -0,0 +1,44 @@
+use digest::{KeyInit, Mac};
+use crate::hasher::Hasher;
+use crate::util::error_exit;
+
+/// Adapts message authentication codes, such as keyed BLAKE2 or HMAC, whose output depends on a
+/// secret key
+pub struct KeyedHasher<M: Mac + KeyInit + 'static> {
+    internal_hasher: Option<M>,
+}
+
+impl <M: Mac + KeyInit + 'static> KeyedHasher<M> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(key: &[u8]) -> Result<Box<dyn Hasher>, String> {
+        let internal_hasher: M = <M as KeyInit>::new_from_slice(key).map_err(|_| {
+            format!("Key of {} bytes has an invalid length for the hash function", key.len())
+        })?;
+
+        Ok(Box::new(Self {
+            internal_hasher: Some(internal_hasher)
+        }))
+    }
+}
+
+impl <M: Mac + KeyInit + 'static> Hasher for KeyedHasher<M> {
+    fn update(&mut self, buffer: &mut [u8]) {
+        if let Some(ref mut hasher) = self.internal_hasher {
+            hasher.update(buffer);
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<u8> {
+        let hasher = self.internal_hasher.take().unwrap_or_else(|| {
+            error_exit(Some("Hasher already finalized".to_string()));
+        });
+        hasher.finalize().into_bytes().to_vec()
+    }
+
+    fn compute_hash(&mut self, buffer: &mut Vec<u8>) -> Vec<u8> {
+        for chunk in buffer.chunks_mut(8192) {
+            self.update(chunk);
+        }
+        self.finalize()
+    }
+}
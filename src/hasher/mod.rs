@@ -1,7 +1,17 @@
-use crate::hasher::default::GenericHasher;
-use crate::models::HashFunctionType;
+use crate::models::{HashFunctionType, XofParameters};
 
+#[cfg(any(feature = "xxh3", feature = "crc32c"))]
+mod checksum;
+#[cfg(any(
+    feature = "md5", feature = "sha1", feature = "sha2", feature = "sha3", feature = "blake2",
+    feature = "ripemd", feature = "sm3", feature = "streebog", feature = "whirlpool"
+))]
 mod default;
+#[cfg(any(feature = "sha2", feature = "sha3", feature = "blake2"))]
+mod keyed;
+pub mod registry;
+#[cfg(feature = "sha3")]
+mod xof;
 
 pub trait Hasher {
 
@@ -12,19 +22,76 @@ pub trait Hasher {
     fn compute_hash(&mut self, buffer: &mut Vec<u8>) -> Vec<u8>;
 }
 
+/// Largest supported output size of extendable-output functions, as a hash value is stored for
+/// every chunk
+pub const MAX_OUTPUT_BITS: u32 = 8192;
+
 pub struct HasherFactory {
 }
 
 impl HasherFactory {
 
-    pub fn get_instance(algorithm: &HashFunctionType) -> Box<dyn Hasher> {
-        match algorithm {
-            HashFunctionType::MD5 => GenericHasher::<::md5::Md5>::new(),
-            HashFunctionType::SHA1 => GenericHasher::<::sha1::Sha1>::new(),
-            HashFunctionType::SHA2_256 => GenericHasher::<::sha2::Sha256>::new(),
-            HashFunctionType::SHA2_512 => GenericHasher::<::sha2::Sha512>::new(),
-            HashFunctionType::SHA3_256 => GenericHasher::<::sha3::Sha3_256>::new(),
-            HashFunctionType::SHA3_512 => GenericHasher::<::sha3::Sha3_512>::new(),
+    /// Get a hasher for the given hash function. The parameters are only considered by
+    /// extendable-output functions, whose output size defaults to twice their security level.
+    pub fn get_instance(algorithm: &HashFunctionType, xof: &XofParameters) -> Box<dyn Hasher> {
+        (algorithm.info().constructor)(xof)
+    }
+
+    /// Get a hasher using the keyed mode of the hash function if a key is given, and a plain hasher
+    /// with the given parameters otherwise
+    pub fn get_hasher(algorithm: &HashFunctionType, xof: &XofParameters, key: Option<&[u8]>) -> Result<Box<dyn Hasher>, String> {
+        match key {
+            Some(key) => Self::get_keyed_instance(algorithm, key),
+            None => Ok(Self::get_instance(algorithm, xof))
+        }
+    }
+
+    /// Ensure the hash function supports the requested output size and customization string
+    pub fn validate_xof_parameters(algorithm: &HashFunctionType, xof: &XofParameters) -> Result<(), String> {
+        if xof.customization.is_some() && !algorithm.is_customizable() {
+            return Err(format!(
+                "Hash function {:?} has no customization string, only cSHAKE supports '--customization'",
+                algorithm
+            ));
+        }
+
+        match xof.output_bits {
+            Some(_) if !algorithm.is_extendable_output() => Err(format!(
+                "Hash function {:?} has a fixed output size, only extendable-output functions support '--output-bits'",
+                algorithm
+            )),
+            Some(output_bits) => Self::validate_output_size(output_bits),
+            None => Ok(())
+        }
+    }
+
+    /// Ensure the output size is a positive multiple of 8 that does not exceed MAX_OUTPUT_BITS
+    pub fn validate_output_size(output_bits: u32) -> Result<(), String> {
+        if output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err(format!("Output size of {output_bits} bits is not a positive multiple of 8"));
+        }
+
+        if output_bits > MAX_OUTPUT_BITS {
+            return Err(format!("Output size of {output_bits} bits exceeds the maximum of {MAX_OUTPUT_BITS} bits"));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the hash function has a keyed mode that supports the given key, if any
+    pub fn validate_key(algorithm: &HashFunctionType, key: Option<&[u8]>) -> Result<(), String> {
+        match key {
+            Some(key) => Self::get_keyed_instance(algorithm, key).map(|_| ()),
+            None => Ok(())
+        }
+    }
+
+    /// Get a hasher whose output additionally depends on the given secret key. Fails if the hash
+    /// function has no keyed mode or the key length is not supported by it.
+    pub fn get_keyed_instance(algorithm: &HashFunctionType, key: &[u8]) -> Result<Box<dyn Hasher>, String> {
+        match algorithm.info().keyed_constructor {
+            Some(keyed_constructor) => keyed_constructor(key),
+            None => Err(format!("Hash function {:?} does not support keyed hashing", algorithm))
         }
     }
 }
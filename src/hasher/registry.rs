@@ -0,0 +1,599 @@
+use std::sync::RwLock;
+#[cfg(feature = "blake2")]
+use blake2::{Blake2b, Blake2bMac, Blake2s, Blake2sMac};
+#[cfg(feature = "blake2")]
+use blake2::digest::consts::{U16, U20, U28, U32, U48, U64};
+#[cfg(any(feature = "sha2", feature = "sha3"))]
+use hmac::Hmac;
+use crate::hasher::Hasher;
+#[cfg(any(feature = "xxh3", feature = "crc32c"))]
+use crate::hasher::checksum::ChecksumHasher;
+#[cfg(any(
+    feature = "md5", feature = "sha1", feature = "sha2", feature = "sha3", feature = "blake2",
+    feature = "ripemd", feature = "sm3", feature = "streebog", feature = "whirlpool"
+))]
+use crate::hasher::default::GenericHasher;
+#[cfg(any(feature = "sha2", feature = "sha3", feature = "blake2"))]
+use crate::hasher::keyed::KeyedHasher;
+#[cfg(feature = "sha3")]
+use crate::hasher::xof::XofHasher;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::models::{HashFunctionType, XofParameters};
+
+/// How much the hash values of a hash function can be trusted
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum SecurityStatus {
+    SECURE,
+    /// Practical attacks are known, so the hash function should only be used for compatibility
+    LEGACY,
+    /// Collisions can be constructed deliberately with little effort, so only accidental changes
+    /// are detected
+    NON_CRYPTOGRAPHIC,
+}
+
+/// Creates a hasher with the given parameters, which only extendable-output functions consider
+pub type HasherConstructor = dyn Fn(&XofParameters) -> Box<dyn Hasher> + Send + Sync;
+
+/// Creates a hasher whose output depends on the given key, failing if the key length is invalid
+pub type KeyedHasherConstructor = dyn Fn(&[u8]) -> Result<Box<dyn Hasher>, String> + Send + Sync;
+
+/// Hash functions registered at runtime via register_hash_function. Entries are never removed, so
+/// they are leaked to hand out references with the same lifetime as the built-in entries.
+static CUSTOM_HASH_FUNCTIONS: RwLock<Vec<&'static HashFunctionInfo>> = RwLock::new(Vec::new());
+
+/// Everything par-hash needs to know about a hash function. Adding a built-in hash function only
+/// requires a variant of HashFunctionType and an entry in HASH_FUNCTIONS.
+pub struct HashFunctionInfo {
+    pub algorithm: HashFunctionType,
+
+    /// Name used on the command line, in configuration files and in the interactive prompt
+    pub name: &'static str,
+
+    /// Alternative names that are accepted as well
+    pub aliases: &'static [&'static str],
+
+    /// Digest size, which is the default for extendable-output functions
+    pub digest_bits: u32,
+
+    /// Whether the digest size can be chosen freely via the output size of the hashing config
+    pub extendable_output: bool,
+
+    /// Whether the hash function accepts the customization string of the hashing config
+    pub customizable: bool,
+
+    pub security: SecurityStatus,
+
+    pub description: &'static str,
+
+    pub constructor: &'static HasherConstructor,
+
+    /// Constructor for the keyed mode of the hash function, if it has one. Hash functions without
+    /// a dedicated keyed mode use HMAC.
+    pub keyed_constructor: Option<&'static KeyedHasherConstructor>,
+}
+
+/// All built-in hash functions in the order they are listed to users
+pub static HASH_FUNCTIONS: &[HashFunctionInfo] = &[
+    #[cfg(feature = "md5")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::MD5,
+        name: "md5",
+        aliases: &[],
+        digest_bits: 128,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::LEGACY,
+        description: "Usage not recommended as practical attacks against MD5 exist, but due to e.g. the small \
+            digest size and fast computation, there are still cases where its usage is appropriate.",
+        constructor: &|_| GenericHasher::<::md5::Md5>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sha1")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA1,
+        name: "sha1",
+        aliases: &["sha-1"],
+        digest_bits: 160,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::LEGACY,
+        description: "Usage not recommended as attacks against SHA1 are known, but there are still cases where \
+            its usage is appropriate.",
+        constructor: &|_| GenericHasher::<::sha1::Sha1>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_224,
+        name: "sha2-224",
+        aliases: &["sha224"],
+        digest_bits: 224,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 224-bit hash size",
+        constructor: &|_| GenericHasher::<::sha2::Sha224>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha224>>::new),
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_256,
+        name: "sha2-256",
+        aliases: &["sha256"],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 256-bit hash size",
+        constructor: &|_| GenericHasher::<::sha2::Sha256>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha256>>::new),
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_384,
+        name: "sha2-384",
+        aliases: &["sha384"],
+        digest_bits: 384,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 384-bit hash size",
+        constructor: &|_| GenericHasher::<::sha2::Sha384>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha384>>::new),
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_512,
+        name: "sha2-512",
+        aliases: &["sha512"],
+        digest_bits: 512,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 512-bit hash size",
+        constructor: &|_| GenericHasher::<::sha2::Sha512>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha512>>::new),
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_512_224,
+        name: "sha2-512-224",
+        aliases: &["sha512-224"],
+        digest_bits: 224,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 512-bit internal state truncated to 224-bit hash size (SHA-512/224)",
+        constructor: &|_| GenericHasher::<::sha2::Sha512_224>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha512_224>>::new),
+    },
+    #[cfg(feature = "sha2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA2_512_256,
+        name: "sha2-512-256",
+        aliases: &["sha512-256"],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA2 with 512-bit internal state truncated to 256-bit hash size (SHA-512/256)",
+        constructor: &|_| GenericHasher::<::sha2::Sha512_256>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha2::Sha512_256>>::new),
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA3_256,
+        name: "sha3-256",
+        aliases: &[],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA3 with 256-bit hash size",
+        constructor: &|_| GenericHasher::<::sha3::Sha3_256>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha3::Sha3_256>>::new),
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHA3_512,
+        name: "sha3-512",
+        aliases: &[],
+        digest_bits: 512,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHA3 with 512-bit hash size",
+        constructor: &|_| GenericHasher::<::sha3::Sha3_512>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Hmac<::sha3::Sha3_512>>::new),
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHAKE128,
+        name: "shake128",
+        aliases: &[],
+        digest_bits: 256,
+        extendable_output: true,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHAKE128 extendable-output function, 256-bit hash size unless specified via --output-bits",
+        constructor: &|xof| XofHasher::<::sha3::Shake128>::new(get_output_size(xof, 256)),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SHAKE256,
+        name: "shake256",
+        aliases: &[],
+        digest_bits: 512,
+        extendable_output: true,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "SHAKE256 extendable-output function, 512-bit hash size unless specified via --output-bits",
+        constructor: &|xof| XofHasher::<::sha3::Shake256>::new(get_output_size(xof, 512)),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::CSHAKE128,
+        name: "cshake128",
+        aliases: &[],
+        digest_bits: 256,
+        extendable_output: true,
+        customizable: true,
+        security: SecurityStatus::SECURE,
+        description: "cSHAKE128, SHAKE128 with a customization string specified via --customization",
+        constructor: &|xof| XofHasher::from_instance(
+            ::sha3::CShake128::from_core(::sha3::CShake128Core::new(get_customization(xof))),
+            get_output_size(xof, 256)
+        ),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sha3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::CSHAKE256,
+        name: "cshake256",
+        aliases: &[],
+        digest_bits: 512,
+        extendable_output: true,
+        customizable: true,
+        security: SecurityStatus::SECURE,
+        description: "cSHAKE256, SHAKE256 with a customization string specified via --customization",
+        constructor: &|xof| XofHasher::from_instance(
+            ::sha3::CShake256::from_core(::sha3::CShake256Core::new(get_customization(xof))),
+            get_output_size(xof, 512)
+        ),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2B_512,
+        name: "blake2b-512",
+        aliases: &["blake2b", "b2"],
+        digest_bits: 512,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2b with 512-bit hash size, as computed by b2sum",
+        constructor: &|_| GenericHasher::<Blake2b<U64>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2bMac<U64>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2B_384,
+        name: "blake2b-384",
+        aliases: &[],
+        digest_bits: 384,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2b with 384-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2b<U48>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2bMac<U48>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2B_256,
+        name: "blake2b-256",
+        aliases: &[],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2b with 256-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2b<U32>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2bMac<U32>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2B_160,
+        name: "blake2b-160",
+        aliases: &[],
+        digest_bits: 160,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2b with 160-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2b<U20>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2bMac<U20>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2S_256,
+        name: "blake2s-256",
+        aliases: &["blake2s"],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2s with 256-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2s<U32>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2sMac<U32>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2S_224,
+        name: "blake2s-224",
+        aliases: &[],
+        digest_bits: 224,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2s with 224-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2s<U28>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2sMac<U28>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2S_160,
+        name: "blake2s-160",
+        aliases: &[],
+        digest_bits: 160,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2s with 160-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2s<U20>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2sMac<U20>>::new),
+    },
+    #[cfg(feature = "blake2")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::BLAKE2S_128,
+        name: "blake2s-128",
+        aliases: &[],
+        digest_bits: 128,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "BLAKE2s with 128-bit hash size",
+        constructor: &|_| GenericHasher::<Blake2s<U16>>::new(),
+        keyed_constructor: Some(&KeyedHasher::<Blake2sMac<U16>>::new),
+    },
+    #[cfg(feature = "ripemd")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::RIPEMD160,
+        name: "ripemd160",
+        aliases: &["ripemd-160", "rmd160"],
+        digest_bits: 160,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::LEGACY,
+        description: "RIPEMD-160, only recommended for compatibility, e.g. with checksums of legacy archives",
+        constructor: &|_| GenericHasher::<::ripemd::Ripemd160>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "sm3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::SM3,
+        name: "sm3",
+        aliases: &[],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "256-bit SM3, the Chinese national standard GB/T 32905-2016",
+        constructor: &|_| GenericHasher::<::sm3::Sm3>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "streebog")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::STREEBOG_256,
+        name: "streebog-256",
+        aliases: &["streebog256", "gost-256"],
+        digest_bits: 256,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "256-bit Streebog, the Russian national standard GOST R 34.11-2012",
+        constructor: &|_| GenericHasher::<::streebog::Streebog256>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "streebog")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::STREEBOG_512,
+        name: "streebog-512",
+        aliases: &["streebog512", "gost-512"],
+        digest_bits: 512,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::SECURE,
+        description: "512-bit Streebog, the Russian national standard GOST R 34.11-2012",
+        constructor: &|_| GenericHasher::<::streebog::Streebog512>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "whirlpool")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::WHIRLPOOL,
+        name: "whirlpool",
+        aliases: &[],
+        digest_bits: 512,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::LEGACY,
+        description: "512-bit Whirlpool, only recommended for compatibility, e.g. with checksums of legacy archives",
+        constructor: &|_| GenericHasher::<::whirlpool::Whirlpool>::new(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "xxh3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::XXH3_64,
+        name: "xxh3-64",
+        aliases: &["xxh3"],
+        digest_bits: 64,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::NON_CRYPTOGRAPHIC,
+        description: "NON-CRYPTOGRAPHIC: 64-bit XXH3, very fast but only suited to detect accidental changes",
+        constructor: &|_| ChecksumHasher::xxh3_64(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "xxh3")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::XXH3_128,
+        name: "xxh3-128",
+        aliases: &["xxh128"],
+        digest_bits: 128,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::NON_CRYPTOGRAPHIC,
+        description: "NON-CRYPTOGRAPHIC: 128-bit XXH3 (XXH128), very fast but only suited to detect accidental \
+            changes",
+        constructor: &|_| ChecksumHasher::xxh3_128(),
+        keyed_constructor: None,
+    },
+    #[cfg(feature = "crc32c")]
+    HashFunctionInfo {
+        algorithm: HashFunctionType::CRC32C,
+        name: "crc32c",
+        aliases: &[],
+        digest_bits: 32,
+        extendable_output: false,
+        customizable: false,
+        security: SecurityStatus::NON_CRYPTOGRAPHIC,
+        description: "NON-CRYPTOGRAPHIC: CRC-32C (Castagnoli) checksum, only suited to detect accidental changes",
+        constructor: &|_| ChecksumHasher::crc32c(),
+        keyed_constructor: None,
+    },
+];
+
+/// Handle of a hash function registered at runtime, identified by its unique name. Serialized as
+/// its name, so hash trees and checkpoints can only be loaded once the hash function is registered.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CustomHashFunction {
+    name: &'static str,
+}
+
+impl CustomHashFunction {
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl Serialize for CustomHashFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomHashFunction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name: String = String::deserialize(deserializer)?;
+
+        match find_hash_function(&name).map(|info| info.algorithm) {
+            Some(HashFunctionType::CUSTOM(custom)) => Ok(custom),
+            _ => Err(serde::de::Error::custom(format!("Custom hash function '{name}' is not registered")))
+        }
+    }
+}
+
+/// Register a custom hash function, e.g. an in-house digest or a legacy checksum, which afterward
+/// is selectable by its name like the built-in hash functions, including on the command line of
+/// par_hash::app::run. Fails if the name is already taken by another hash function.
+pub fn register_hash_function<F>(
+    name: &str,
+    description: &str,
+    digest_bits: u32,
+    security: SecurityStatus,
+    constructor: F
+) -> Result<HashFunctionType, String>
+where
+    F: Fn() -> Box<dyn Hasher> + Send + Sync + 'static
+{
+    if name.trim().is_empty() || name.contains(char::is_whitespace) {
+        return Err(format!("'{name}' is not a valid name for a hash function"));
+    }
+
+    let mut custom_hash_functions = CUSTOM_HASH_FUNCTIONS.write().unwrap();
+
+    // Checked while holding the lock, so concurrent registrations cannot claim the same name
+    let normalized_name: String = normalize_name(name);
+    if HASH_FUNCTIONS.iter().chain(custom_hash_functions.iter().copied()).any(|info| has_name(info, &normalized_name)) {
+        return Err(format!("A hash function named '{name}' already exists"));
+    }
+
+    let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+    let algorithm: HashFunctionType = HashFunctionType::CUSTOM(CustomHashFunction { name });
+
+    custom_hash_functions.push(Box::leak(Box::new(HashFunctionInfo {
+        algorithm,
+        name,
+        aliases: &[],
+        digest_bits,
+        extendable_output: false,
+        customizable: false,
+        security,
+        description: Box::leak(description.to_string().into_boxed_str()),
+        constructor: Box::leak(Box::new(move |_: &XofParameters| constructor())),
+        keyed_constructor: None,
+    })));
+
+    Ok(algorithm)
+}
+
+/// All built-in and registered hash functions, the latter in the order of their registration
+pub fn get_hash_functions() -> Vec<&'static HashFunctionInfo> {
+    let custom_hash_functions = CUSTOM_HASH_FUNCTIONS.read().unwrap();
+    HASH_FUNCTIONS.iter().chain(custom_hash_functions.iter().copied()).collect()
+}
+
+/// Look up a hash function by its name or one of its aliases. Case and the use of '-' or '_' as
+/// separator are ignored, so e.g. "SHA2_256" refers to the same hash function as "sha2-256".
+pub fn find_hash_function(name: &str) -> Option<&'static HashFunctionInfo> {
+    let normalized_name: String = normalize_name(name);
+
+    get_hash_functions().into_iter().find(|info| has_name(info, &normalized_name))
+}
+
+/// Look up the registry entry of the hash function
+pub fn get_hash_function_info(algorithm: &HashFunctionType) -> Option<&'static HashFunctionInfo> {
+    match algorithm {
+        HashFunctionType::CUSTOM(custom) => CUSTOM_HASH_FUNCTIONS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|info| info.name == custom.name)
+            .copied(),
+        #[allow(unreachable_patterns)] // if built without any hash function features
+        _ => HASH_FUNCTIONS.iter().find(|info| info.algorithm == *algorithm)
+    }
+}
+
+fn has_name(info: &HashFunctionInfo, normalized_name: &str) -> bool {
+    normalize_name(info.name) == normalized_name
+        || info.aliases.iter().any(|alias| normalize_name(alias) == normalized_name)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', "-")
+}
+
+#[cfg(feature = "sha3")]
+fn get_output_size(xof: &XofParameters, default_bits: u32) -> usize {
+    (xof.output_bits.unwrap_or(default_bits) / 8) as usize
+}
+
+#[cfg(feature = "sha3")]
+fn get_customization(xof: &XofParameters) -> &[u8] {
+    xof.customization.as_deref().unwrap_or_default().as_bytes()
+}
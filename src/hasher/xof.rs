@@ -0,0 +1,53 @@
+use digest::{ExtendableOutput, Update};
+use crate::hasher::Hasher;
+use crate::util::error_exit;
+
+/// Adapts extendable-output functions, such as SHAKE, whose digest size is chosen by the caller
+/// instead of being fixed by the algorithm
+pub struct XofHasher<X: Update + ExtendableOutput + 'static> {
+    internal_hasher: Option<X>,
+    output_size: usize, // in bytes
+}
+
+impl <X: Default + Update + ExtendableOutput + 'static> XofHasher<X> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(output_size: usize) -> Box<dyn Hasher> {
+        Self::from_instance(X::default(), output_size)
+    }
+}
+
+impl <X: Update + ExtendableOutput + 'static> XofHasher<X> {
+    /// Adapt an already initialized instance, e.g. of cSHAKE, whose customization string is
+    /// passed on creation
+    pub fn from_instance(hasher: X, output_size: usize) -> Box<dyn Hasher> {
+        Box::new(Self {
+            internal_hasher: Some(hasher),
+            output_size
+        })
+    }
+}
+
+impl <X: Update + ExtendableOutput + 'static> Hasher for XofHasher<X> {
+    fn update(&mut self, buffer: &mut [u8]) {
+        if let Some(ref mut hasher) = self.internal_hasher {
+            hasher.update(buffer);
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<u8> {
+        let hasher = self.internal_hasher.take().unwrap_or_else(|| {
+            error_exit(Some("Hasher already finalized".to_string()));
+        });
+
+        let mut output: Vec<u8> = vec![0u8; self.output_size];
+        hasher.finalize_xof_into(&mut output);
+        output
+    }
+
+    fn compute_hash(&mut self, buffer: &mut Vec<u8>) -> Vec<u8> {
+        for chunk in buffer.chunks_mut(8192) {
+            self.update(chunk);
+        }
+        self.finalize()
+    }
+}
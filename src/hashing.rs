@@ -1,149 +1,304 @@
 use std::cmp::min;
 use std::fs::{DirEntry, File, Metadata};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Error, ErrorKind, Read};
 use std::sync::Arc;
-use std::sync::mpsc::Sender;
-use crate::models::{HashFunctionType, HashingConfig, InternalStateUpdate};
+use crate::models::{HashFunctionType, HashedChunk, HashedEntry, HashingConfig};
 use rayon::prelude::*;
+use crate::cancellation::CancellationToken;
+use crate::checkpoint::Checkpoint;
 use crate::hasher::{Hasher, HasherFactory};
 use crate::merkle_tree::MerkleTree;
 use crate::models::TargetType::{DIRECTORY, FILE};
-use crate::util::error_exit;
-use crate::util::fs::{get_dir_entry, get_file, get_metadata, is_supported_filetype, read_chunk};
+use crate::observer::HashObserver;
+use crate::util::fs::{get_entry_name, is_supported_filetype, join_relative_path, read_chunk};
 use crate::util::math::{gb_to_bytes, mb_to_bytes};
 
+/// Number of bytes read from a file after which the progress of hashing it is reported
+const PROGRESS_UPDATE_SIZE: u64 = 16_000_000;
+
 pub struct HashComputer {
     config: HashingConfig,
-    hash_algorithm: HashFunctionType,
-    progress_tx: Option<Sender<InternalStateUpdate>>, // Sender to the progress tracker thread
+    hash_algorithms: Vec<HashFunctionType>, // Hash functions computed in the same pass, the first one is reported to observers
+    observers: Vec<Box<dyn HashObserver>>,
+    cancellation: CancellationToken,
+    key: Option<Vec<u8>>, // Secret key of keyed hash functions
+    resumed_checkpoint: Option<Checkpoint>, // Files hashed during a previous, unfinished run
 }
 
 impl HashComputer {
 
     /// Initialize a new HashComputer instance that contains all metadata/config needed to compute
-    /// the target's hash value
+    /// the target's hash value. The observers are notified about the progress of the computation,
+    /// which stops once the cancellation token is cancelled. If a key is given, file contents,
+    /// directory metadata and all merkle tree nodes are hashed using the keyed mode of the hash
+    /// function, which fails if the hash function does not support the key.
     pub fn new(
         config: HashingConfig,
         algorithm: HashFunctionType,
-        progress_tx: Option<Sender<InternalStateUpdate>>
-    ) -> Arc<Self> {
-        Arc::new(HashComputer {
+        observers: Vec<Box<dyn HashObserver>>,
+        cancellation: CancellationToken,
+        key: Option<Vec<u8>>
+    ) -> std::io::Result<Arc<Self>> {
+        Self::with_algorithms(config, vec![algorithm], observers, cancellation, key)
+    }
+
+    /// Initialize a HashComputer that computes the hash trees of several hash functions while
+    /// reading every file only once. Observers receive the entries of the first hash function.
+    pub fn with_algorithms(
+        config: HashingConfig,
+        algorithms: Vec<HashFunctionType>,
+        observers: Vec<Box<dyn HashObserver>>,
+        cancellation: CancellationToken,
+        key: Option<Vec<u8>>
+    ) -> std::io::Result<Arc<Self>> {
+        validate_algorithms(&algorithms, key.as_deref())?;
+
+        Ok(Arc::new(HashComputer {
+            config,
+            hash_algorithms: algorithms,
+            observers,
+            cancellation,
+            key,
+            resumed_checkpoint: None
+        }))
+    }
+
+    /// Initialize a HashComputer that reuses the hash values of all files in the checkpoint that
+    /// were not modified since. The checkpoint has to be created with the same algorithm, config
+    /// and key.
+    pub fn resume(
+        config: HashingConfig,
+        algorithm: HashFunctionType,
+        observers: Vec<Box<dyn HashObserver>>,
+        cancellation: CancellationToken,
+        key: Option<Vec<u8>>,
+        checkpoint: Checkpoint
+    ) -> std::io::Result<Arc<Self>> {
+
+        if checkpoint.algorithm != algorithm || checkpoint.config != config {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Checkpoint was created with hash function {:?} and config {:?}",
+                    checkpoint.algorithm, checkpoint.config
+                )
+            ));
+        }
+
+        validate_algorithms(&[algorithm], key.as_deref())?;
+
+        if !checkpoint.is_created_with_key(key.as_deref()) {
+            return Err(Error::new(ErrorKind::InvalidInput, "Checkpoint was created with a different key"));
+        }
+
+        Ok(Arc::new(HashComputer {
             config,
-            hash_algorithm: algorithm,
-            progress_tx
-        })
+            hash_algorithms: vec![algorithm],
+            observers,
+            cancellation,
+            key,
+            resumed_checkpoint: Some(checkpoint)
+        }))
+    }
+
+    /// Compute the target's hash value, retaining the hashes of all directory entries and file
+    /// chunks it was derived from. Fails with `ErrorKind::Interrupted` if the computation was
+    /// cancelled, or with the error of an entry that could not be read, which the observers are
+    /// notified about beforehand. If several hash functions are computed, the tree of the first one
+    /// is returned.
+    pub fn compute_hash_tree(&self, target: PathBuf) -> std::io::Result<HashedEntry> {
+        Ok(self.compute_hash_trees(target)?.swap_remove(0))
+    }
+
+    /// Compute the target's hash tree for every hash function, in the order they were specified
+    pub fn compute_hash_trees(&self, target: PathBuf) -> std::io::Result<Vec<HashedEntry>> {
+        let result: std::io::Result<Vec<HashedEntry>> = self.compute_entry_hash(target.clone(), "");
+
+        if result.as_ref().is_err_and(|e| e.kind() == ErrorKind::Interrupted) {
+            self.notify(|observer| observer.on_cancelled(&target));
+        }
+
+        result
     }
 
-    pub fn compute_hash(&self, target: PathBuf) -> Vec<u8> {
+    /// Compute the hash trees of the entry, whose path relative to the hashed root is given
+    fn compute_entry_hash(&self, target: PathBuf, relative_path: &str) -> std::io::Result<Vec<HashedEntry>> {
+        self.check_cancellation()?;
 
         if target.is_file() {
             self.abstract_compute_file_hash(target)
         } else if target.is_dir() {
-            self.compute_directory_hash(target)
+            self.compute_directory_hash(target, relative_path)
         } else {
-            error_exit(Some(format!("Path {:?} references neither a file nor a directory!", target)));
+            let error: Error = Error::new(ErrorKind::InvalidInput, "Path references neither a file nor a directory");
+            Err(self.fail(&target, "Unable to hash", error))
         }
     }
 
-    fn compute_directory_hash(&self, path: PathBuf) -> Vec<u8> {
+    fn compute_directory_hash(&self, path: PathBuf, relative_path: &str) -> std::io::Result<Vec<HashedEntry>> {
+
+        self.notify(|observer| observer.on_entry_started(&path, DIRECTORY, 0));
 
         let directory_entries: Vec<DirEntry> = fs::read_dir(&path)
-            .unwrap_or_else(|e| {
-                error_exit(Some(format!("Unable to unwrap entry of directory at path '{:?}': {e:?}", path)));
-            })
-            .map(|entry| get_dir_entry(&path, entry))
+            .and_then(|entries| entries.collect::<std::io::Result<Vec<DirEntry>>>())
+            .map_err(|e| self.fail(&path, "Unable to read directory", e))?
+            .into_iter()
             .filter(|entry| is_supported_filetype(&entry.path()))
             .collect();
 
         // Concurrently compute the hash value of each directory entry
-        let mut dir_entry_hashes: Vec<Vec<u8>> = directory_entries
+        let children: Vec<Vec<HashedEntry>> = directory_entries
             .par_iter() // Maintains the order of the entries => hash value reproducible
-            .map(|directory_entry| self.compute_hash(directory_entry.path()))
+            .map(|directory_entry| {
+                let name: String = directory_entry.file_name().to_string_lossy().to_string();
+                self.compute_entry_hash(directory_entry.path(), &join_relative_path(relative_path, &name))
+            })
+            .collect::<std::io::Result<Vec<Vec<HashedEntry>>>>()?;
+
+        let entries: Vec<HashedEntry> = self.hash_algorithms
+            .iter()
+            .zip(transpose(children, self.hash_algorithms.len()))
+            .map(|(algorithm, children)| {
+
+                // Hash directory metadata that should be considered when computing the directories final
+                // hash. The path is relative to the hashed root, so identical directories hash the same
+                // regardless of where the hashed target is located.
+                let mut hasher_name: Box<dyn Hasher> = self.get_hasher(algorithm);
+                let metadata_hash: Vec<u8> = hasher_name.compute_hash(&mut relative_path.as_bytes().to_vec());
+
+                let mut entry: HashedEntry = HashedEntry {
+                    name: get_entry_name(&path),
+                    target_type: DIRECTORY,
+                    size: children.iter().map(|child| child.size).sum(),
+                    hash: vec![],
+                    metadata_hash,
+                    chunks: vec![],
+                    children,
+                };
+
+                // From all obtained hash values, compute a merkle tree and get the hash value of its root node
+                entry.hash = self.get_merkle_root(algorithm, &entry);
+                entry
+            })
             .collect();
 
-        // Add hashed directory metadata that should be considered when computing the directories final hash
-        let mut hasher_name: Box<dyn Hasher> = HasherFactory::get_instance(&self.hash_algorithm);
-        dir_entry_hashes.push(hasher_name.compute_hash(&mut path.to_string_lossy().as_bytes().to_vec()));
-
-        // From all obtained hash values, compute a merkle tree and get the hash value of its root node
-        let mut tree: MerkleTree = MerkleTree::new(&self.hash_algorithm);
-        tree.initialize_from_vector(&dir_entry_hashes);
-
-        self.send_internal_state_update(InternalStateUpdate {
-            target_type: DIRECTORY,
-            processed_bytes: None
-        });
-
-        tree.get_root_hash()
+        self.notify(|observer| observer.on_directory_completed(&path, &entries[0]));
+        Ok(entries)
     }
 
-    fn abstract_compute_file_hash(&self, path: PathBuf) -> Vec<u8> {
+    fn abstract_compute_file_hash(&self, path: PathBuf) -> std::io::Result<Vec<HashedEntry>> {
+
+        let file_metadata: Metadata = path.metadata().map_err(|e| self.fail(&path, "Unable to get metadata", e))?;
 
-        let file_metadata = get_metadata(&path);
+        let entry: HashedEntry = HashedEntry {
+            name: get_entry_name(&path),
+            target_type: FILE,
+            size: file_metadata.len(),
+            hash: vec![],
+            metadata_hash: vec![],
+            chunks: vec![],
+            children: vec![],
+        };
 
-        if self.config.split_threshold.is_none() {
-            let res = self.compute_simple_file_hash(path);
+        self.notify(|observer| observer.on_entry_started(&path, FILE, file_metadata.len()));
 
-            self.send_file_update(file_metadata.len());
-            return res;
+        // Checkpoints are only resumed when computing a single hash function
+        if let Some(checkpoint) = &self.resumed_checkpoint
+            && let Some(checkpointed_entry) = checkpoint.get_file_entry(&path, &file_metadata)
+        {
+            self.notify(|observer| observer.on_bytes_processed(&path, checkpointed_entry.size));
+            self.notify(|observer| observer.on_entry_finished(&path, checkpointed_entry));
+            return Ok(vec![checkpointed_entry.clone()]);
         }
 
-        let res = if file_metadata.len() >= self.config.split_threshold.unwrap() {
-            self.compute_chunked_file_hash(path)
+        // Empty files have no chunks, so they are always hashed as a whole
+        let is_chunked: bool = file_metadata.len() > 0
+            && self.config.split_threshold.is_some_and(|threshold| file_metadata.len() >= threshold);
+
+        let entries: Vec<HashedEntry> = if is_chunked {
+            let chunks: Vec<Vec<HashedChunk>> = self.compute_chunked_file_hash(&path, &file_metadata)?;
+
+            self.hash_algorithms
+                .iter()
+                .zip(transpose(chunks, self.hash_algorithms.len()))
+                .map(|(algorithm, chunks)| {
+                    let mut entry: HashedEntry = HashedEntry { chunks, ..entry.clone() };
+                    entry.hash = self.get_merkle_root(algorithm, &entry);
+                    entry
+                })
+                .collect()
         } else {
-            self.compute_simple_file_hash(path)
+            self.compute_simple_file_hash(&path)?
+                .into_iter()
+                .map(|hash| HashedEntry { hash, ..entry.clone() })
+                .collect()
         };
 
-        self.send_file_update(file_metadata.len());
-        res
+        self.notify(|observer| observer.on_entry_finished(&path, &entries[0]));
+        Ok(entries)
     }
 
-    fn compute_simple_file_hash(&self, path: PathBuf) -> Vec<u8> {
-        let file: File = get_file(&path);
+    /// Compute the hash value of the whole file for every hash function in a single pass
+    fn compute_simple_file_hash(&self, path: &PathBuf) -> std::io::Result<Vec<Vec<u8>>> {
+        let file: File = File::open(path).map_err(|e| self.fail(path, "Unable to open file", e))?;
         let mut reader: BufReader<File> = BufReader::new(file);
 
-        let mut hasher: Box<dyn Hasher> = HasherFactory::get_instance(&self.hash_algorithm);
+        let mut hashers: Vec<Box<dyn Hasher>> = self.hash_algorithms
+            .iter()
+            .map(|algorithm| self.get_hasher(algorithm))
+            .collect();
 
         // To not waste memory, we do not load the entire file into memory at once but read in chunks
         let mut buffer= [0u8; 8192];
+        let mut unreported_bytes: u64 = 0;
 
         loop {
-            let n = reader.read(&mut buffer).unwrap_or_else(|e| {
-                error_exit(Some(format!("Unable to read from file '{:?}': {e:?}", path)));
-            });
+            self.check_cancellation()?;
+
+            let n = reader.read(&mut buffer).map_err(|e| self.fail(path, "Unable to read from file", e))?;
 
             if n == 0 {
                 break;
             }
 
-            hasher.update(&mut buffer);
+            for hasher in hashers.iter_mut() {
+                hasher.update(&mut buffer[..n]);
+            }
+
+            // Periodically report the progress, so hashing large files does not appear to stall
+            unreported_bytes += n as u64;
+            if unreported_bytes >= PROGRESS_UPDATE_SIZE {
+                self.notify(|observer| observer.on_bytes_processed(path, unreported_bytes));
+                unreported_bytes = 0;
+            }
+        }
+
+        if unreported_bytes > 0 {
+            self.notify(|observer| observer.on_bytes_processed(path, unreported_bytes));
         }
 
-        hasher.finalize()
+        Ok(hashers.iter_mut().map(|hasher| hasher.finalize()).collect())
     }
 
-    fn compute_chunked_file_hash(&self, path: PathBuf) -> Vec<u8> {
+    /// Hash the chunks of the file in parallel. For each chunk, the hashes of all hash functions
+    /// are returned.
+    fn compute_chunked_file_hash(&self, path: &PathBuf, metadata: &Metadata) -> std::io::Result<Vec<Vec<HashedChunk>>> {
 
         // Compute chunk ranges to prevent having to read the whole file into memory at once
-        let metadata: Metadata = get_metadata(&path);
-        let chunk_size = self.get_chunk_size(&metadata);
+        let chunk_size = self.get_chunk_size(metadata);
         let mut chunk_ranges: Vec<(u64, u64)> = vec![];
 
-        for i in (0.. metadata.len()).step_by(chunk_size+1) {
+        for i in (0.. metadata.len()).step_by(chunk_size) {
             let end = min(metadata.len(), i + chunk_size as u64);
             chunk_ranges.push((i, end));
         }
 
-        let chunk_hashes: Vec<Vec<u8>> = self.process_chunks(&path, chunk_ranges);
-        let mut tree: MerkleTree = MerkleTree::new(&self.hash_algorithm);
-        tree.initialize_from_vector(&chunk_hashes);
-
-        tree.get_root_hash()
+        self.process_chunks(path, &chunk_ranges)
     }
 
-    fn process_chunks(&self, path: &PathBuf, chunk_ranges: Vec<(u64, u64)>) -> Vec<Vec<u8>> {
+    fn process_chunks(&self, path: &PathBuf, chunk_ranges: &[(u64, u64)]) -> std::io::Result<Vec<Vec<HashedChunk>>> {
         chunk_ranges
             .par_iter()
             .map(|range| {
@@ -152,14 +307,40 @@ impl HashComputer {
             .collect()
     }
 
-    fn compute_file_chunk_hash(&self, path: &PathBuf, range: &(u64, u64)) -> Vec<u8> {
-        let mut chunk = read_chunk(path, range.0, range.1);
-        HasherFactory::get_instance(&self.hash_algorithm).compute_hash(&mut chunk)
+    fn compute_file_chunk_hash(&self, path: &PathBuf, range: &(u64, u64)) -> std::io::Result<Vec<HashedChunk>> {
+        self.check_cancellation()?;
+
+        let mut chunk: Vec<u8> = read_chunk(path, range.0, range.1)
+            .map_err(|e| self.fail(path, "Unable to read chunk from file", e))?;
+
+        let hashed_chunks: Vec<HashedChunk> = self.hash_algorithms
+            .iter()
+            .map(|algorithm| HashedChunk {
+                offset: range.0,
+                length: range.1 - range.0,
+                hash: self.get_hasher(algorithm).compute_hash(&mut chunk)
+            })
+            .collect();
+
+        self.notify(|observer| observer.on_chunk_finished(path, &hashed_chunks[0]));
+        Ok(hashed_chunks)
+    }
+
+    fn get_merkle_root(&self, algorithm: &HashFunctionType, entry: &HashedEntry) -> Vec<u8> {
+        // The key was validated when creating the HashComputer
+        let mut tree: MerkleTree = MerkleTree::new(algorithm, &self.config.xof, self.key.as_deref()).unwrap();
+        tree.initialize_from_vector(&entry.get_leaf_hashes().unwrap());
+        tree.get_root_hash()
+    }
+
+    fn get_hasher(&self, algorithm: &HashFunctionType) -> Box<dyn Hasher> {
+        // The key was validated when creating the HashComputer
+        HasherFactory::get_hasher(algorithm, &self.config.xof, self.key.as_deref()).unwrap()
     }
 
     fn get_chunk_size(&self, metadata: &Metadata) -> usize {
-        if self.config.chunk_size.is_some() {
-            return self.config.chunk_size.unwrap() as usize;
+        if let Some(chunk_size) = self.config.chunk_size {
+            return chunk_size as usize;
         }
 
         let file_size = metadata.len();
@@ -178,20 +359,158 @@ impl HashComputer {
         mb_to_bytes(256) as usize
     }
 
-    fn send_file_update(&self, size: u64) {
-        self.send_internal_state_update(InternalStateUpdate {
-            target_type: FILE,
-            processed_bytes: Some(size)
-        });
+    fn check_cancellation(&self) -> std::io::Result<()> {
+        if self.cancellation.is_cancelled() {
+            return Err(Error::new(ErrorKind::Interrupted, "Hash computation was cancelled"));
+        }
+
+        Ok(())
+    }
+
+    fn notify<F: Fn(&dyn HashObserver)>(&self, event: F) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
+    }
+
+    /// Notify the observers about the error and add the affected path to it, so it can be returned
+    /// to the caller, which aborts the computation
+    fn fail(&self, path: &Path, msg: &str, error: Error) -> Error {
+        self.notify(|observer| observer.on_error(path, &error));
+        Error::new(error.kind(), format!("{msg} '{}': {error}", path.display()))
     }
 
-    fn send_internal_state_update(&self, update: InternalStateUpdate) {
-        let tx: Option<Sender<InternalStateUpdate>> = self.progress_tx.clone();
+}
+
+/// Turn a list of per-entry results, each holding one value per hash function, into one list of
+/// entries per hash function
+fn transpose<T>(values: Vec<Vec<T>>, width: usize) -> Vec<Vec<T>> {
+    let mut transposed: Vec<Vec<T>> = (0..width).map(|_| Vec::with_capacity(values.len())).collect();
 
-        match tx {
-            Some(tx) => tx.send(update).unwrap_or_else(|_e| {}),
-            None => {}
+    for row in values {
+        for (column, value) in row.into_iter().enumerate() {
+            transposed[column].push(value);
         }
     }
 
+    transposed
+}
+
+/// Ensure at least one hash function is given and all of them support the key, if any
+fn validate_algorithms(algorithms: &[HashFunctionType], key: Option<&[u8]>) -> std::io::Result<()> {
+    if algorithms.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "At least one hash function is required to compute a hash tree"));
+    }
+
+    for algorithm in algorithms {
+        HasherFactory::validate_key(algorithm, key).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sha2"))]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use crate::cancellation::CancellationToken;
+    use crate::checkpoint::{import_checkpoint, CheckpointWriter};
+    use crate::models::XofParameters;
+    use super::*;
+
+    fn create_tree(root: &Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            let path: PathBuf = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    fn chunked_config(chunk_size: u64) -> HashingConfig {
+        HashingConfig { split_threshold: Some(1), chunk_size: Some(chunk_size), keyed: false, xof: XofParameters::default() }
+    }
+
+    fn hash_tree(target: &Path, config: HashingConfig) -> HashedEntry {
+        HashComputer::new(config, HashFunctionType::SHA2_256, vec![], CancellationToken::new(), None)
+            .unwrap()
+            .compute_hash_tree(target.to_path_buf())
+            .unwrap()
+    }
+
+    #[test]
+    fn chunks_cover_every_byte_of_the_file() {
+        let path: PathBuf = std::env::temp_dir().join(format!("par-hash-chunks-{}", std::process::id()));
+        fs::write(&path, b"0123456789").unwrap();
+        let original: HashedEntry = hash_tree(&path, chunked_config(4));
+
+        // The byte right after the first chunk used to be skipped
+        fs::write(&path, b"0123X56789").unwrap();
+        let modified: HashedEntry = hash_tree(&path, chunked_config(4));
+        fs::remove_file(&path).unwrap();
+
+        let ranges: Vec<(u64, u64)> = original.chunks.iter().map(|chunk| (chunk.offset, chunk.length)).collect();
+        assert_eq!(ranges, vec![(0, 4), (4, 4), (8, 2)]);
+        assert_ne!(original.hash, modified.hash);
+    }
+
+    #[test]
+    fn resuming_from_a_checkpoint_yields_the_same_hash() {
+        let base: PathBuf = std::env::temp_dir().join(format!("par-hash-resume-{}", std::process::id()));
+        let root: PathBuf = base.join("root");
+        let checkpoint_file: PathBuf = base.join("checkpoint.json");
+        create_tree(&root, &[("a", "first file"), ("sub/b", "second file"), ("sub/nested/c", "third file")]);
+
+        let checkpoint: Checkpoint = Checkpoint::new(HashFunctionType::SHA2_256, chunked_config(4), root.clone(), None).unwrap();
+        let writer: CheckpointWriter = CheckpointWriter::new(checkpoint_file.clone(), Duration::from_secs(60), checkpoint);
+
+        let hash_computer: Arc<HashComputer> = HashComputer::new(
+            chunked_config(4), HashFunctionType::SHA2_256, vec![Box::new(writer)], CancellationToken::new(), None
+        ).unwrap();
+        let original: HashedEntry = hash_computer.compute_hash_tree(root.clone()).unwrap();
+
+        // The final checkpoint is written once the writer is dropped along with the HashComputer
+        drop(hash_computer);
+
+        let checkpoint: Checkpoint = import_checkpoint(&checkpoint_file).unwrap();
+        assert_eq!(checkpoint.files.len(), 3);
+
+        let hash_computer: Arc<HashComputer> = HashComputer::resume(
+            chunked_config(4), HashFunctionType::SHA2_256, vec![], CancellationToken::new(), None, checkpoint
+        ).unwrap();
+        let resumed: HashedEntry = hash_computer.compute_hash_tree(root.clone()).unwrap();
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(original.hash, resumed.hash);
+    }
+
+    #[test]
+    fn single_pass_over_several_hash_functions_yields_the_same_hashes() {
+        let root: PathBuf = std::env::temp_dir().join(format!("par-hash-single-pass-{}", std::process::id()));
+        create_tree(&root, &[("a", "chunked file"), ("sub/b", "x"), ("sub/empty", "")]);
+
+        let algorithms: Vec<HashFunctionType> = vec![HashFunctionType::SHA2_256, HashFunctionType::SHA2_512];
+        let hash_computer: Arc<HashComputer> = HashComputer::with_algorithms(
+            chunked_config(4), algorithms.clone(), vec![], CancellationToken::new(), None
+        ).unwrap();
+        let single_pass: Vec<Vec<u8>> = hash_computer
+            .compute_hash_trees(root.clone())
+            .unwrap()
+            .into_iter()
+            .map(|tree| tree.hash)
+            .collect();
+
+        let separate_runs: Vec<Vec<u8>> = algorithms
+            .into_iter()
+            .map(|algorithm| {
+                HashComputer::new(chunked_config(4), algorithm, vec![], CancellationToken::new(), None)
+                    .unwrap()
+                    .compute_hash_tree(root.clone())
+                    .unwrap()
+                    .hash
+            })
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(single_pass, separate_runs);
+    }
 }
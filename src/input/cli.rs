@@ -1,5 +1,14 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
 use std::path::PathBuf;
-use clap::{Parser, ValueEnum};
+use clap::{Arg, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::ErrorKind;
+use crate::hasher::HasherFactory;
+use crate::hasher::registry::{find_hash_function, get_hash_functions};
+use crate::models::HashFunctionType;
+use crate::util::size::{parse_size, validate_chunk_size, validate_split_size};
+use crate::input::config::apply_profile;
 
 #[derive(Parser)]
 #[command(name = "par-hash")]
@@ -10,49 +19,319 @@ use clap::{Parser, ValueEnum};
 #[command(next_line_help = true)]
 pub struct Cli {
 
-    /// Path to input file or directory whose hash should be computed. If not specified as cli
-    /// argument, the path is queried interactively during runtime.
-    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
-    pub input: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub options: GlobalOptions,
+
+    /// Arguments of the hash command, which is run if no command is specified
+    #[command(flatten)]
+    pub hash: HashArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compute the hash value of a file or directory
+    Hash(HashArgs),
+    /// Check a file or directory against an expected hash value or a previously exported hash tree
+    Verify(VerifyArgs),
+    /// Write the hash value of every file below a target to a manifest file
+    Manifest(ManifestArgs),
+    /// Report how a file or directory differs from another target or a previously exported hash tree
+    Diff(DiffArgs),
+    /// Find files with identical content and report the space they waste
+    Dupes(DupesArgs),
+    /// Measure the throughput of the supported hash functions
+    Bench(BenchArgs),
+}
+
+/// Options shared by all commands
+#[derive(Args, Clone)]
+pub struct GlobalOptions {
+
+    /// Profile of the configuration file (./.par-hash.toml or $XDG_CONFIG_HOME/par-hash/config.toml)
+    /// whose options are used, unless specified on the command line. Defaults to the profile
+    /// named "default", if it exists.
+    #[arg(long, value_name = "PROFILE", required = false, global = true)]
+    pub profile: Option<String>,
 
     /// Hashing function to be used for computing the file/folder hash. If not specified as cli
     /// argument, the desired hash function to be used is queried interactively during runtime.
-    #[arg(value_enum, short, long, required = false)]
-    pub algorithm: Option<HashFunctionType>,
+    /// The hash and bench commands accept a comma-separated list, e.g. md5,sha2-256,sha2-512,
+    /// whose hash values are computed in a single pass over the data.
+    #[arg(short, long, value_parser = HashFunctionParser, value_delimiter = ',', required = false, global = true)]
+    pub algorithm: Vec<HashFunctionType>,
 
-    /// File size threshold (in bytes) at which a file should be split into chunks to parallelize
-    /// the computation of its hash value
-    #[arg(short, long, value_name = "SPLIT THRESHOLD", required = false)]
+    /// File size threshold at which a file should be split into chunks to parallelize the
+    /// computation of its hash value. Accepts bytes or units such as 512k, 64MiB or 1G, where k, M,
+    /// G and T are decimal (powers of 1000) and KiB, MiB, GiB and TiB binary (powers of 1024) units.
+    #[arg(short, long, value_name = "SPLIT THRESHOLD", value_parser = parse_split_size, required = false, global = true)]
     pub split_size: Option<u64>,
 
-    /// When a file is split into fixed size chunks, this option specifies the chunk size. If the
-    /// file size is not a multiple of the chosen chunk size, the last chunk will be smaller.
-    #[arg(short, long, value_name = "CHUNK SIZE", required = false)]
+    /// When a file is split into fixed size chunks, this option specifies the chunk size (at most
+    /// 1GiB, same units as the split threshold). If the file size is not a multiple of the chosen
+    /// chunk size, the last chunk will be smaller. Chosen based on the file size if not specified.
+    #[arg(short, long, value_name = "CHUNK SIZE", value_parser = parse_chunk_size, required = false, global = true)]
     pub chunk_size: Option<u64>,
 
+    /// File containing the secret key for keyed hashing, which uses the keyed mode of BLAKE2 and
+    /// HMAC for SHA2 and SHA3 hash functions. The file's content is used as is, including any
+    /// trailing newline.
+    #[arg(long, value_name = "KEY FILE", required = false, global = true, conflicts_with = "key_env")]
+    pub key_file: Option<PathBuf>,
+
+    /// Environment variable containing the secret key for keyed hashing, as alternative to
+    /// --key-file. Keys are never accepted as plain argument, as those are visible to other users.
+    #[arg(long, value_name = "VARIABLE", required = false, global = true)]
+    pub key_env: Option<String>,
+
+    /// Hash size in bits of extendable-output functions such as SHAKE128 and SHAKE256. Has to be a
+    /// positive multiple of 8.
+    #[arg(long, value_name = "BITS", value_parser = parse_output_bits, required = false, global = true)]
+    pub output_bits: Option<u32>,
+
+    /// Customization string of cSHAKE128 and cSHAKE256, which yields unrelated hash values for
+    /// different applications hashing the same data
+    #[arg(long, value_name = "STRING", required = false, global = true)]
+    pub customization: Option<String>,
+
+    /// Number of threads used to compute hash values. Defaults to the number of logical CPUs.
+    #[arg(short, long, value_name = "THREADS", required = false, global = true)]
+    pub threads: Option<usize>,
+
+    /// File the hash values of all completely hashed files are periodically written to, so an
+    /// interrupted run can be resumed
+    #[arg(long, value_name = "CHECKPOINT", required = false, global = true)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Resume an interrupted run from the given checkpoint, reusing the hash values of all files
+    /// that were not modified since. The checkpoint is continued unless --checkpoint is specified.
+    #[arg(long, value_name = "CHECKPOINT", required = false, global = true)]
+    pub resume: Option<PathBuf>,
+
+    /// Interval in seconds in which the checkpoint is written
+    #[arg(long, value_name = "SECONDS", value_parser = parse_checkpoint_interval, required = false, default_value = "60", global = true)]
+    pub checkpoint_interval: u64,
+
     /// When specified, no progress information is displayed
-    #[arg(short, long, required = false, default_value = "false")]
+    #[arg(short, long, required = false, default_value = "false", global = true)]
     pub no_progress: bool,
+
+    /// How progress information is displayed. By default, a detailed overview is continuously
+    /// redrawn if stdout is a terminal, while plain progress lines are written otherwise.
+    #[arg(value_enum, long, required = false, default_value = "auto", global = true)]
+    pub progress: ProgressMode,
+
+    /// When specified, the total number of files, directories and bytes to process is not
+    /// determined, which otherwise requires an additional traversal of the target
+    #[arg(long, required = false, default_value = "false", global = true)]
+    pub no_totals: bool,
+
+    /// File descriptor plain and JSON progress information is written to. Defaults to stderr.
+    #[cfg(unix)]
+    #[arg(long, value_name = "FD", required = false, global = true)]
+    pub progress_fd: Option<i32>,
+
+    /// Format in which results are printed
+    #[arg(value_enum, long, required = false, default_value = "text", global = true)]
+    pub output_format: OutputFormat,
+
+    /// Never query missing arguments interactively, but fail instead. Automatically enabled if
+    /// stdin is not a terminal.
+    #[arg(long, required = false, default_value = "false", global = true)]
+    pub non_interactive: bool,
+
+    /// Path used if the interactive query for the input path is left empty. Defaults to the
+    /// current working directory.
+    #[arg(long, value_name = "FILE|FOLDER PATH", required = false, global = true)]
+    pub default_input: Option<PathBuf>,
+
+    /// Hash function used if the interactive query for the hash function is left empty. Defaults to
+    /// md5, or the first available hash function if par-hash was built without it.
+    #[arg(long, value_parser = HashFunctionParser, required = false, global = true)]
+    pub default_algorithm: Option<HashFunctionType>,
+}
+
+#[derive(Args, Clone, Default)]
+pub struct HashArgs {
+
+    /// Path to input file or directory whose hash should be computed. If not specified as cli
+    /// argument, the path is queried interactively during runtime.
+    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
+    pub input: Option<PathBuf>,
+
+    /// File the full hash tree (directory structure, entry hashes and chunk hashes) is written to
+    /// once the hash value has been computed
+    #[arg(long, value_name = "TREE FILE", required = false)]
+    pub export_tree: Option<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct VerifyArgs {
+
+    /// Path to the file or directory that should be verified. If neither this nor an expected hash
+    /// value is specified, only the integrity of the given hash tree is validated.
+    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
+    pub input: Option<PathBuf>,
+
+    /// Hex-encoded hash value the target's hash value has to match
+    #[arg(short, long, value_name = "HASH", required_unless_present = "tree", conflicts_with = "tree")]
+    pub expected: Option<String>,
+
+    /// Previously exported hash tree the target's hash value has to match. The hash function and
+    /// chunking settings stored in the tree are used unless they are explicitly specified.
+    #[arg(long, value_name = "TREE FILE", required = false)]
+    pub tree: Option<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct ManifestArgs {
+
+    /// Path to the file or directory whose files should be listed in the manifest. If not specified
+    /// as cli argument, the path is queried interactively during runtime.
+    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
+    pub input: Option<PathBuf>,
+
+    /// File the manifest is written to. In text format, every line contains the hash value and
+    /// the relative path of a file. In JSON format, the full hash tree is written.
+    #[arg(short, long, value_name = "MANIFEST FILE", required = true)]
+    pub output: PathBuf,
+}
+
+#[derive(Args, Clone)]
+pub struct DiffArgs {
+
+    /// Path to the file or directory that is compared. If not specified as cli argument, the path
+    /// is queried interactively during runtime.
+    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
+    pub input: Option<PathBuf>,
+
+    /// File or directory the input is compared against. Reports all entries that were added,
+    /// removed or modified in the input relative to this target.
+    #[arg(long, value_name = "FILE|FOLDER PATH", required_unless_present = "tree", conflicts_with = "tree")]
+    pub against: Option<PathBuf>,
+
+    /// Previously exported hash tree the input is compared against. The hash function and
+    /// chunking settings stored in the tree are used unless they are explicitly specified.
+    #[arg(long, value_name = "TREE FILE", required = false)]
+    pub tree: Option<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct DupesArgs {
+
+    /// Files and directories that are searched for files with identical content
+    #[arg(value_name = "FILE|FOLDER PATH", required = true, num_args = 1..)]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct BenchArgs {
+
+    /// File or directory that is hashed with every hash function. If not specified, a generated
+    /// buffer is hashed in memory instead.
+    #[arg(short, long, value_name = "FILE|FOLDER PATH", required = false)]
+    pub input: Option<PathBuf>,
+
+    /// Size of the in-memory buffer that is hashed if no input is specified
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, required = false, default_value = "256MiB")]
+    pub size: u64,
 }
 
-#[derive(ValueEnum, Clone, PartialEq, Debug)]
-pub enum HashFunctionType {
-    /// Usage not recommended as practical attacks against MD5 exist, but due to e.g. the small
-    /// digest size and fast computation, there are still cases where its usage is appropriate.
-    MD5,
-    /// Usage not recommended as attacks against SHA1 are known, but there are still cases where
-    /// its usage is appropriate.
-    SHA1,
-    /// SHA2 with 256-bit hash size
-    SHA2_256,
-    /// SHA2 with 512-bit hash size
-    SHA2_512,
-    /// SHA3 with 256-bit hash size
-    SHA3_256,
-    /// SHA3 with 512-bit hash size
-    SHA3_512
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ProgressMode {
+    /// Terminal overview if stdout is a terminal, plain progress lines otherwise
+    AUTO,
+    /// Continuously redrawn overview using ANSI escape codes
+    TERMINAL,
+    /// Periodic progress lines without escape codes, suitable for log files
+    PLAIN,
+    /// Newline-delimited JSON progress events
+    JSON,
+    /// No progress information
+    NONE,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum OutputFormat {
+    /// Human-readable output
+    TEXT,
+    /// Machine-readable JSON written to stdout
+    JSON,
+}
+
+/// Parses hash functions by the names and aliases of the registry, which also provides the
+/// possible values listed in the help
+#[derive(Clone)]
+pub struct HashFunctionParser;
+
+impl TypedValueParser for HashFunctionParser {
+    type Value = HashFunctionType;
+
+    fn parse_ref(&self, cmd: &clap::Command, arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, clap::Error> {
+        let value: Cow<str> = value.to_string_lossy();
+
+        find_hash_function(&value).map(|info| info.algorithm).ok_or_else(|| {
+            let arg: String = arg.map(|arg| arg.to_string()).unwrap_or_default();
+            let names: Vec<&str> = get_hash_functions().iter().map(|info| info.name).collect();
+
+            cmd.clone().error(
+                ErrorKind::InvalidValue,
+                format!("invalid value '{value}' for '{arg}'\n  [possible values: {}]", names.join(", "))
+            )
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(get_hash_functions().into_iter().map(|info| {
+            PossibleValue::new(info.name).aliases(info.aliases.iter().copied()).help(info.description)
+        })))
+    }
+}
+
+pub fn parse_split_size(value: &str) -> Result<u64, String> {
+    let split_size: u64 = parse_size(value)?;
+    validate_split_size(split_size)?;
+    Ok(split_size)
+}
+
+pub fn parse_chunk_size(value: &str) -> Result<u64, String> {
+    let chunk_size: u64 = parse_size(value)?;
+    validate_chunk_size(chunk_size)?;
+    Ok(chunk_size)
+}
+
+pub fn parse_output_bits(value: &str) -> Result<u32, String> {
+    let output_bits: u32 = value.parse().map_err(|e| format!("'{value}' is not a valid number of bits: {e}"))?;
+    HasherFactory::validate_output_size(output_bits)?;
+    Ok(output_bits)
+}
+
+pub fn parse_checkpoint_interval(value: &str) -> Result<u64, String> {
+    let interval: u64 = value.parse().map_err(|e| format!("'{value}' is not a valid number of seconds: {e}"))?;
+
+    if interval == 0 {
+        return Err("Checkpoint interval must be at least one second".to_string());
+    }
+
+    Ok(interval)
 }
 
 pub fn parse_cli_arguments() -> Cli {
-    Cli::parse()
+    let matches: ArgMatches = Cli::command().get_matches();
+    let mut cli: Cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // Arguments of the implicit hash command must not be mixed with an explicit command
+    let has_hash_args = cli.hash.input.is_some() || cli.hash.export_tree.is_some();
+    if cli.command.is_some() && has_hash_args {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, "'--input' and '--export-tree' have to be specified after the command")
+            .exit();
+    }
+
+    apply_profile(&mut cli.options, &matches);
+    cli
 }
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use clap::ArgMatches;
+use clap::ValueEnum;
+use clap::parser::ValueSource;
+use serde::{Deserialize, Deserializer};
+use crate::util::error_exit;
+use crate::models::HashFunctionType;
+use crate::input::cli::{parse_chunk_size, parse_output_bits, parse_split_size, GlobalOptions, OutputFormat, ProgressMode};
+
+/// Name of the configuration file looked up in the current working directory
+const PROJECT_CONFIG_FILE: &str = ".par-hash.toml";
+
+/// Profile that is applied if no profile is explicitly selected
+const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// Named set of options. Options specified on the command line take precedence.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default, deserialize_with = "deserialize_hash_functions")]
+    pub algorithm: Option<Vec<HashFunctionType>>,
+
+    #[serde(default, deserialize_with = "deserialize_split_size")]
+    pub split_size: Option<u64>, // in bytes
+
+    #[serde(default, deserialize_with = "deserialize_chunk_size")]
+    pub chunk_size: Option<u64>, // in bytes
+
+    #[serde(default, deserialize_with = "deserialize_output_bits")]
+    pub output_bits: Option<u32>,
+
+    pub customization: Option<String>,
+
+    pub threads: Option<usize>,
+
+    #[serde(default, deserialize_with = "deserialize_value_enum")]
+    pub progress: Option<ProgressMode>,
+
+    pub no_totals: Option<bool>,
+
+    #[serde(default, deserialize_with = "deserialize_value_enum")]
+    pub output_format: Option<OutputFormat>,
+
+    pub non_interactive: Option<bool>,
+}
+
+/// Apply the selected profile, or the default profile if none is selected, to all options that
+/// were not specified on the command line
+pub fn apply_profile(options: &mut GlobalOptions, matches: &ArgMatches) {
+    let config_file: Option<PathBuf> = get_config_file();
+
+    let mut config: ConfigFile = match &config_file {
+        Some(config_file) => load_config_file(config_file),
+        None => ConfigFile::default()
+    };
+
+    let profile: Profile = match &options.profile {
+        Some(name) => config.profiles.remove(name).unwrap_or_else(|| match &config_file {
+            Some(config_file) => error_exit(Some(format!("Profile '{name}' is not defined in {:?}", config_file))),
+            None => error_exit(Some(format!("Profile '{name}' is not defined, as no configuration file exists")))
+        }),
+        None => config.profiles.remove(DEFAULT_PROFILE).unwrap_or_default()
+    };
+
+    apply_option(matches, "algorithm", &mut options.algorithm, profile.algorithm);
+    apply_option(matches, "split_size", &mut options.split_size, profile.split_size.map(Some));
+    apply_option(matches, "chunk_size", &mut options.chunk_size, profile.chunk_size.map(Some));
+    apply_option(matches, "output_bits", &mut options.output_bits, profile.output_bits.map(Some));
+    apply_option(matches, "customization", &mut options.customization, profile.customization.map(Some));
+    apply_option(matches, "threads", &mut options.threads, profile.threads.map(Some));
+    apply_option(matches, "progress", &mut options.progress, profile.progress);
+    apply_option(matches, "no_totals", &mut options.no_totals, profile.no_totals);
+    apply_option(matches, "output_format", &mut options.output_format, profile.output_format);
+    apply_option(matches, "non_interactive", &mut options.non_interactive, profile.non_interactive);
+}
+
+fn apply_option<T>(matches: &ArgMatches, id: &str, option: &mut T, profile_value: Option<T>) {
+    if let Some(value) = profile_value && matches.value_source(id) != Some(ValueSource::CommandLine) {
+        *option = value;
+    }
+}
+
+/// The configuration file of the current project takes precedence over the user's configuration
+fn get_config_file() -> Option<PathBuf> {
+    let project_config: PathBuf = PathBuf::from(PROJECT_CONFIG_FILE);
+    if project_config.is_file() {
+        return Some(project_config);
+    }
+
+    let config_home: PathBuf = env::var_os("XDG_CONFIG_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    let user_config: PathBuf = config_home.join("par-hash").join("config.toml");
+    user_config.is_file().then_some(user_config)
+}
+
+fn load_config_file(path: &PathBuf) -> ConfigFile {
+    let content: String = fs::read_to_string(path).unwrap_or_else(|e| {
+        error_exit(Some(format!("Unable to read configuration file {:?}: {e:?}", path)));
+    });
+
+    toml::from_str(&content).unwrap_or_else(|e| {
+        error_exit(Some(format!("Invalid configuration file {:?}: {e}", path)));
+    })
+}
+
+/// Values are accepted in the same notation as on the command line
+fn deserialize_value_enum<'de, D: Deserializer<'de>, T: ValueEnum>(deserializer: D) -> Result<Option<T>, D::Error> {
+    let value: String = String::deserialize(deserializer)?;
+
+    T::from_str(&value, true)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Hash functions are accepted either as list or comma-separated, by the same names and aliases as
+/// on the command line
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HashFunctionsValue {
+    List(Vec<String>),
+    Text(String),
+}
+
+fn deserialize_hash_functions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<HashFunctionType>>, D::Error> {
+    let values: Vec<String> = match HashFunctionsValue::deserialize(deserializer)? {
+        HashFunctionsValue::List(values) => values,
+        HashFunctionsValue::Text(value) => value.split(',').map(|value| value.trim().to_string()).collect()
+    };
+
+    values
+        .iter()
+        .map(|value| HashFunctionType::from_str(value))
+        .collect::<Result<Vec<HashFunctionType>, _>>()
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Sizes are accepted either as number of bytes or in the same notation as on the command line
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SizeValue {
+    Bytes(u64),
+    Text(String),
+}
+
+fn deserialize_split_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let value: String = match SizeValue::deserialize(deserializer)? {
+        SizeValue::Bytes(bytes) => bytes.to_string(),
+        SizeValue::Text(value) => value
+    };
+
+    parse_split_size(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_chunk_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let value: String = match SizeValue::deserialize(deserializer)? {
+        SizeValue::Bytes(bytes) => bytes.to_string(),
+        SizeValue::Text(value) => value
+    };
+
+    parse_chunk_size(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_output_bits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    let output_bits: u32 = u32::deserialize(deserializer)?;
+
+    parse_output_bits(&output_bits.to_string()).map(Some).map_err(serde::de::Error::custom)
+}
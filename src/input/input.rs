@@ -1,28 +1,23 @@
-use std::io::{Error, ErrorKind, Write};
+use std::io::{Error, ErrorKind, IsTerminal, Write};
 use std::path::PathBuf;
 use std::env;
 use std::str::FromStr;
-use crate::models::HashFunctionType;
-use crate::input::cli::Cli;
+use crate::hasher::HasherFactory;
+use crate::models::{HashFunctionType, XofParameters};
+use crate::input::cli::GlobalOptions;
 use crate::util::error_exit;
 use crate::util::fs::is_supported_filetype;
 
 fn query_cli_line(prompt: &str) -> std::io::Result<String> {
 
     print!("{}", prompt);
-    match std::io::stdout().flush() {
-        Err(e) => {
-            return Err(Error::new(ErrorKind::Other, format!("Unable to print user prompt: {e:?}")));
-        },
-        _ => {}
+    if let Err(e) = std::io::stdout().flush() {
+        return Err(Error::other(format!("Unable to print user prompt: {e:?}")));
     }
 
     let mut input = String::new();
-    match std::io::stdin().read_line(&mut input) {
-        Err(e) => {
-            return Err(Error::new(ErrorKind::Other, format!("Unable to query prompt response: {e:?}")));
-        },
-        _ => {}
+    if let Err(e) = std::io::stdin().read_line(&mut input) {
+        return Err(Error::other(format!("Unable to query prompt response: {e:?}")));
     }
 
     if input.ends_with("\n") {
@@ -32,7 +27,7 @@ fn query_cli_line(prompt: &str) -> std::io::Result<String> {
     Ok(input.trim().to_string())
 }
 
-fn validate_hash_target(target: &PathBuf) -> std::io::Result<()> {
+pub fn validate_hash_target(target: &PathBuf) -> std::io::Result<()> {
 
     if !target.exists() {
         return Err(Error::new(
@@ -58,15 +53,28 @@ fn get_current_working_directory() -> PathBuf {
     }
 }
 
-fn query_hash_target() -> PathBuf {
-    let prompt= "Enter the file or directory path: [Empty for CWD]\n> ";
+/// Missing arguments are only queried if the user explicitly allows it and is able to answer
+fn is_interactive(options: &GlobalOptions) -> bool {
+    !options.non_interactive && std::io::stdin().is_terminal()
+}
+
+fn get_default_hash_target(options: &GlobalOptions) -> PathBuf {
+    match &options.default_input {
+        Some(default_input) => default_input.clone(),
+        None => get_current_working_directory()
+    }
+}
 
-    let target: String = query_cli_line(prompt).unwrap_or_else(|e| {
+fn query_hash_target(options: &GlobalOptions) -> PathBuf {
+    let default_target: PathBuf = get_default_hash_target(options);
+    let prompt = format!("Enter the file or directory path: [Empty for {:?}]\n> ", default_target);
+
+    let target: String = query_cli_line(prompt.as_str()).unwrap_or_else(|e| {
         error_exit(Some(format!("An error occurred while querying the target path: {e:?}")));
     });
 
     if target.is_empty() {
-        get_current_working_directory()
+        default_target
     } else {
         PathBuf::from(target)
     }
@@ -84,46 +92,97 @@ fn validate_hash_function(algorithm: &String) -> std::io::Result<()> {
     }
 }
 
-fn query_hash_function() -> String {
-    let prompt = format!("Choose one of the following supported hash functions: {}\n[Empty for MD5]\n> ", HashFunctionType::str_overview());
+fn query_hash_function(options: &GlobalOptions) -> String {
+    let default_algorithm: String = options.default_algorithm
+        .or_else(|| HashFunctionType::from_str("md5").ok())
+        .or_else(|| HashFunctionType::all().next())
+        .unwrap_or_else(|| error_exit(Some("par-hash was built without any hash functions".to_string())))
+        .info().name.to_string();
+    let prompt = format!(
+        "Choose one of the following supported hash functions: {}\n[Empty for {default_algorithm}]\n> ",
+        HashFunctionType::str_overview()
+    );
 
     let response = query_cli_line(prompt.as_str()).unwrap_or_else(|e| {
         error_exit(Some(format!("An error occurred while querying the desired hash function: {e:?}")));
     });
 
     if response.is_empty() {
-        String::from_str("md5").unwrap()
+        default_algorithm
     } else {
         response
     }
 }
 
-pub fn get_hash_target(cli: &Cli) -> PathBuf {
+pub fn get_hash_target(input: &Option<PathBuf>, options: &GlobalOptions) -> PathBuf {
 
-    let target: PathBuf = match &cli.input {
+    let target: PathBuf = match input {
         Some(value) => value.clone(),
-        None => query_hash_target()
+        None if is_interactive(options) => query_hash_target(options),
+        None => error_exit(Some(
+            "No input path specified! Pass it via '--input', as interactive queries are disabled.".to_string()
+        ))
     };
 
-    match validate_hash_target(&target) {
-        Err(e) => error_exit(Some(format!("Invalid target path provided: {e:?}"))),
-        _ => {}
+    if let Err(e) = validate_hash_target(&target) {
+        error_exit(Some(format!("Invalid target path provided: {e:?}")));
     }
 
     target
 }
 
-pub fn get_hash_function(cli: &Cli) -> HashFunctionType {
-
-    let hashing_algorithm: String = match &cli.algorithm {
-        Some(val) => format!("{:?}", val),
-        None => query_hash_function()
+/// Get the hash functions whose hash values are computed in a single pass. The interactive query
+/// accepts a comma-separated list as well.
+pub fn get_hash_functions(options: &GlobalOptions) -> Vec<HashFunctionType> {
+
+    let hash_functions: Vec<HashFunctionType> = if !options.algorithm.is_empty() {
+        options.algorithm.clone()
+    } else if is_interactive(options) {
+        query_hash_function(options)
+            .split(',')
+            .map(|hashing_algorithm| {
+                if let Err(e) = validate_hash_function(&hashing_algorithm.to_string()) {
+                    error_exit(Some(format!("{e:?}")));
+                }
+
+                HashFunctionType::from_str(hashing_algorithm).unwrap()
+            })
+            .collect()
+    } else {
+        error_exit(Some(
+            "No hash function specified! Pass it via '--algorithm', as interactive queries are disabled.".to_string()
+        ))
     };
 
-    match validate_hash_function(&hashing_algorithm) {
-        Err(e) => error_exit(Some(format!("{e:?}"))),
-        _ => {}
+    for (i, hash_function) in hash_functions.iter().enumerate() {
+        if hash_functions[..i].contains(hash_function) {
+            error_exit(Some(format!("Hash function {:?} is specified more than once", hash_function)));
+        }
+    }
+
+    let xof: XofParameters = XofParameters { output_bits: options.output_bits, customization: options.customization.clone() };
+
+    // With several hash functions, the output size and customization string only have to be
+    // supported by one of them
+    if !hash_functions.iter().any(|hash_function| HasherFactory::validate_xof_parameters(hash_function, &xof).is_ok())
+        && let Err(e) = HasherFactory::validate_xof_parameters(&hash_functions[0], &xof)
+    {
+        error_exit(Some(e));
+    }
+
+    hash_functions
+}
+
+/// Get the hash function of commands that only support a single one
+pub fn get_hash_function(options: &GlobalOptions) -> HashFunctionType {
+    let hash_functions: Vec<HashFunctionType> = get_hash_functions(options);
+
+    if hash_functions.len() > 1 {
+        error_exit(Some(format!(
+            "This command supports only a single hash function, but {} were specified",
+            hash_functions.len()
+        )));
     }
 
-    HashFunctionType::from_str(hashing_algorithm.as_str()).unwrap()
+    hash_functions[0]
 }
@@ -1,2 +1,4 @@
 pub mod cli;
+pub mod config;
+#[allow(clippy::module_inception)]
 pub mod input;
@@ -0,0 +1,20 @@
+//! Parallel computation of hash trees over files and directories
+
+#[cfg(feature = "cli")]
+pub mod app;
+pub mod cancellation;
+pub mod checkpoint;
+pub mod hasher;
+pub mod hashing;
+pub mod observer;
+pub mod progress_tracker;
+pub mod util;
+pub mod models;
+pub mod merkle_tree;
+pub mod diff;
+pub mod dupes;
+pub mod snapshot;
+#[cfg(feature = "cli")]
+mod commands;
+#[cfg(feature = "cli")]
+mod input;
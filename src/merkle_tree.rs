@@ -1,9 +1,12 @@
-use crate::hasher::HasherFactory;
-use crate::models::HashFunctionType;
+use crate::hasher::{Hasher, HasherFactory};
+use crate::models::{HashFunctionType, XofParameters};
 use crate::util::error_exit;
 
 pub struct MerkleTree {
     algorithm: HashFunctionType,
+    xof: XofParameters,
+    key: Option<Vec<u8>>, // Secret key of keyed hash functions
+    leaf_count: usize,
     root_node: Option<Box<MerkleNode>>
 }
 
@@ -16,12 +19,19 @@ struct MerkleNode {
 
 impl MerkleTree {
 
-    /// Create a new uninitialized merkle tree instance
-    pub fn new(algorithm: &HashFunctionType) -> Self {
-        MerkleTree {
-            algorithm: algorithm.clone(),
+    /// Create a new uninitialized merkle tree instance. If a key is given, inner nodes are hashed
+    /// using the keyed mode of the hash function, just like the leaves. Fails if the hash function
+    /// does not support the key.
+    pub fn new(algorithm: &HashFunctionType, xof: &XofParameters, key: Option<&[u8]>) -> Result<Self, String> {
+        HasherFactory::validate_key(algorithm, key)?;
+
+        Ok(MerkleTree {
+            algorithm: *algorithm,
+            xof: xof.clone(),
+            key: key.map(|key| key.to_vec()),
+            leaf_count: 0,
             root_node: None
-        }
+        })
     }
 
     pub fn get_root_hash(&self) -> Vec<u8> {
@@ -33,9 +43,27 @@ impl MerkleTree {
     }
 
     pub fn initialize_from_vector(&mut self, entries: &Vec<Vec<u8>>) {
+        self.leaf_count = entries.len();
         self.root_node = self.private_initialize_from_vector(entries, 0, entries.len() - 1);
     }
 
+    /// Get the indices of all leaves whose hashes differ from the corresponding leaves of the other
+    /// tree. Both trees need to be built from the same number of leaves. Subtrees with identical
+    /// hash values are skipped without visiting their leaves.
+    pub fn get_differing_leaves(&self, other: &MerkleTree) -> Vec<usize> {
+        if self.leaf_count != other.leaf_count {
+            error_exit(Some("Attempted to compare merkle trees with a different number of leaves!".to_string()));
+        }
+
+        let mut differing_leaves: Vec<usize> = vec![];
+
+        if let (Some(own_root), Some(other_root)) = (&self.root_node, &other.root_node) {
+            collect_differing_leaves(own_root, other_root, 0, self.leaf_count - 1, &mut differing_leaves);
+        }
+
+        differing_leaves
+    }
+
     fn private_initialize_from_vector(&mut self, entries: &Vec<Vec<u8>>, start: usize, end: usize) -> Option<Box<MerkleNode>> {
 
         // Leaf node
@@ -60,7 +88,10 @@ impl MerkleTree {
             end
         );
 
-        let hash = HasherFactory::get_instance(&self.algorithm).compute_hash(
+        // The key was validated when creating the tree
+        let mut hasher: Box<dyn Hasher> = HasherFactory::get_hasher(&self.algorithm, &self.xof, self.key.as_deref()).unwrap();
+
+        let hash = hasher.compute_hash(
             &mut concat_hashes(
                 left_child.clone(),
                 right_child.clone()
@@ -72,6 +103,29 @@ impl MerkleTree {
 
 }
 
+fn collect_differing_leaves(a: &MerkleNode, b: &MerkleNode, start: usize, end: usize, differing_leaves: &mut Vec<usize>) {
+
+    if a.hash == b.hash {
+        return;
+    }
+
+    if start == end {
+        differing_leaves.push(start);
+        return;
+    }
+
+    // Both trees have the same shape, so the children cover the same leaf ranges they were built from
+    let middle = start + (end-start)/2;
+
+    if let (Some(a_left), Some(b_left)) = (&a.left_child, &b.left_child) {
+        collect_differing_leaves(a_left, b_left, start, middle, differing_leaves);
+    }
+
+    if let (Some(a_right), Some(b_right)) = (&a.right_child, &b.right_child) {
+        collect_differing_leaves(a_right, b_right, middle + 1, end, differing_leaves);
+    }
+}
+
 fn concat_hashes(left: Option<Box<MerkleNode>>, right: Option<Box<MerkleNode>>) -> Vec<u8> {
 
     match (left, right) {
@@ -1,6 +1,8 @@
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
-use crate::models::HashFunctionType::{MD5, SHA1, SHA2_256, SHA2_512, SHA3_256, SHA3_512};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::hasher::registry::{find_hash_function, get_hash_function_info, get_hash_functions, CustomHashFunction, HashFunctionInfo, SecurityStatus};
+use crate::util::error_exit;
 
 #[derive(Debug, Default)]
 pub struct InternalState {
@@ -11,61 +13,255 @@ pub struct InternalState {
     pub nr_of_processed_sub_dirs: u64,
     pub nr_of_processed_files: u64,
     pub processed_size: u64, // in bytes
+
+    pub totals_status: TotalsStatus,
+    pub cancelled: bool,
+    pub last_processed_path: Option<PathBuf>,
+    pub files_in_progress: HashMap<PathBuf, u64>, // size of each file that is currently hashed
+}
+
+/// Whether the total number of files, directories and bytes to process is known
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum TotalsStatus {
+    /// Totals are not determined at all
+    #[default]
+    SKIPPED,
+    /// Totals are still being discovered, the current values are lower bounds
+    SCANNING,
+    COMPLETE,
 }
 
 #[derive(Debug)]
 pub struct InternalStateUpdate {
+    pub update_type: UpdateType,
     pub target_type: TargetType,
-    pub processed_bytes: Option<u64>
+    pub processed_bytes: Option<u64>,
+    pub path: PathBuf,
 }
 
 #[derive(Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum UpdateType {
+    STARTED { size: u64 }, // in bytes
+    PROGRESSED, // part of the entry was processed
+    FINISHED,
+    CANCELLED, // hashing of the whole target was cancelled
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum TargetType {
     FILE,
     DIRECTORY,
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct HashingConfig {
     pub split_threshold: Option<u64>, // in bytes
     pub chunk_size: Option<u64>, // in bytes
+
+    /// Whether the hash values depend on a secret key, which itself is never stored
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keyed: bool,
+
+    #[serde(flatten)]
+    pub xof: XofParameters,
+}
+
+/// Parameters of extendable-output functions, which all other hash functions ignore
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct XofParameters {
+    /// Digest size, if it differs from the default of the hash function
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_bits: Option<u32>,
+
+    /// Customization string of cSHAKE, which yields unrelated hash values for different
+    /// applications hashing the same data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub customization: Option<String>,
+}
+
+/// Hashed file or directory, retaining everything needed to rebuild the merkle tree its hash value
+/// was derived from without having to re-read the underlying data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashedEntry {
+    pub name: String,
+    pub target_type: TargetType,
+    pub size: u64, // in bytes, for directories the accumulated size of all contained files
+
+    #[serde(with = "hex")]
+    pub hash: Vec<u8>,
+
+    /// Hash of the directory metadata that is included in the directory's merkle tree
+    #[serde(with = "hex", default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata_hash: Vec<u8>,
+
+    /// Chunks of a file whose hash was computed in parallel, in the order of their offsets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<HashedChunk>,
+
+    /// Entries of a directory, in the order they were hashed in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<HashedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashedChunk {
+    pub offset: u64, // in bytes
+    pub length: u64, // in bytes
+
+    #[serde(with = "hex")]
+    pub hash: Vec<u8>,
+}
+
+impl HashedEntry {
+
+    /// Returns the leaves of the merkle tree the entry's hash value is the root of, or None if the
+    /// hash value was computed directly from the file content
+    pub fn get_leaf_hashes(&self) -> Option<Vec<Vec<u8>>> {
+        match self.target_type {
+            TargetType::DIRECTORY => {
+                let mut leaves: Vec<Vec<u8>> = self.children.iter().map(|child| child.hash.clone()).collect();
+                leaves.push(self.metadata_hash.clone());
+                Some(leaves)
+            },
+            TargetType::FILE if !self.chunks.is_empty() => {
+                Some(self.chunks.iter().map(|chunk| chunk.hash.clone()).collect())
+            },
+            TargetType::FILE => None
+        }
+    }
 }
 
-/// Hash functions supported by par-hash
-#[derive(Debug, PartialEq, Clone, Copy, EnumIter)]
+/// Hash functions supported by par-hash, whose properties are described by the registry
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
 pub enum HashFunctionType {
+    #[cfg(feature = "md5")]
     MD5,
+    #[cfg(feature = "sha1")]
     SHA1,
+    #[cfg(feature = "sha2")]
+    SHA2_224,
+    #[cfg(feature = "sha2")]
     SHA2_256,
+    #[cfg(feature = "sha2")]
+    SHA2_384,
+    #[cfg(feature = "sha2")]
     SHA2_512,
+    #[cfg(feature = "sha2")]
+    SHA2_512_224,
+    #[cfg(feature = "sha2")]
+    SHA2_512_256,
+    #[cfg(feature = "sha3")]
     SHA3_256,
+    #[cfg(feature = "sha3")]
     SHA3_512,
+    #[cfg(feature = "blake2")]
+    BLAKE2B_512,
+    #[cfg(feature = "blake2")]
+    BLAKE2B_384,
+    #[cfg(feature = "blake2")]
+    BLAKE2B_256,
+    #[cfg(feature = "blake2")]
+    BLAKE2B_160,
+    #[cfg(feature = "blake2")]
+    BLAKE2S_256,
+    #[cfg(feature = "blake2")]
+    BLAKE2S_224,
+    #[cfg(feature = "blake2")]
+    BLAKE2S_160,
+    #[cfg(feature = "blake2")]
+    BLAKE2S_128,
+    #[cfg(feature = "xxh3")]
+    XXH3_64,
+    #[cfg(feature = "xxh3")]
+    XXH3_128,
+    #[cfg(feature = "crc32c")]
+    CRC32C,
+    #[cfg(feature = "sha3")]
+    SHAKE128,
+    #[cfg(feature = "sha3")]
+    SHAKE256,
+    #[cfg(feature = "sha3")]
+    CSHAKE128,
+    #[cfg(feature = "sha3")]
+    CSHAKE256,
+    #[cfg(feature = "ripemd")]
+    RIPEMD160,
+    #[cfg(feature = "sm3")]
+    SM3,
+    #[cfg(feature = "streebog")]
+    STREEBOG_256,
+    #[cfg(feature = "streebog")]
+    STREEBOG_512,
+    #[cfg(feature = "whirlpool")]
+    WHIRLPOOL,
+    /// Hash function registered at runtime
+    CUSTOM(CustomHashFunction),
+}
+
+/// Formats hash functions in the notation of the variants of built-in hash functions, e.g.
+/// SHA2_256, which custom hash functions use as well
+impl std::fmt::Debug for HashFunctionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.info().name.to_uppercase().replace('-', "_"))
+    }
 }
 
 impl std::str::FromStr for HashFunctionType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "md5" => Ok(MD5),
-            "sha1" => Ok(SHA1),
-            "sha2_256" => Ok(SHA2_256),
-            "sha2_512" => Ok(SHA2_512),
-            "sha3_256" => Ok(SHA3_256),
-            "sha3_512" => Ok(SHA3_512),
-            _ => Err(format!("'{s}' is not a supported hashing algorithm!"))
+        match find_hash_function(s) {
+            Some(info) => Ok(info.algorithm),
+            None => Err(format!("'{s}' is not a supported hashing algorithm!"))
         }
     }
 }
 
 impl HashFunctionType {
 
+    /// All supported hash functions in the order of the registry
+    pub fn all() -> impl Iterator<Item = HashFunctionType> {
+        get_hash_functions().into_iter().map(|info| info.algorithm)
+    }
+
+    pub fn info(&self) -> &'static HashFunctionInfo {
+        get_hash_function_info(self).unwrap_or_else(|| {
+            // Cannot be formatted via Debug, which relies on the registry entry itself
+            error_exit(Some("Hash function is missing in the registry".to_string()));
+        })
+    }
+
     pub fn str_overview() -> String {
         let mut supported_types: Vec<String> = vec![];
 
-        for supported_type in HashFunctionType::iter() {
-            supported_types.push(format!("{:?}", supported_type));
+        for info in get_hash_functions() {
+            match info.security {
+                SecurityStatus::SECURE => supported_types.push(info.name.to_string()),
+                SecurityStatus::LEGACY => supported_types.push(format!("{} (legacy)", info.name)),
+                SecurityStatus::NON_CRYPTOGRAPHIC => supported_types.push(format!("{} (non-cryptographic)", info.name)),
+            }
         }
 
         supported_types.join(", ")
     }
+
+    /// Non-cryptographic hash functions only detect accidental changes, as collisions can be
+    /// constructed deliberately with little effort
+    pub fn is_cryptographic(&self) -> bool {
+        self.info().security != SecurityStatus::NON_CRYPTOGRAPHIC
+    }
+
+    /// Extendable-output functions produce digests of arbitrary size
+    pub fn is_extendable_output(&self) -> bool {
+        self.info().extendable_output
+    }
+
+    /// Customizable hash functions yield unrelated hash values for different customization strings
+    pub fn is_customizable(&self) -> bool {
+        self.info().customizable
+    }
 }
@@ -0,0 +1,79 @@
+use std::io::Error;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use crate::models::{HashedChunk, HashedEntry, InternalStateUpdate, TargetType, UpdateType};
+
+/// Receives lifecycle events while a HashComputer hashes a target. Events are emitted concurrently
+/// from the worker threads, so implementations have to be thread-safe. All methods default to
+/// doing nothing, so only the events of interest have to be implemented.
+pub trait HashObserver: Send + Sync {
+
+    /// Hashing of a file or directory started. For directories, the size is not known yet and 0.
+    fn on_entry_started(&self, _path: &Path, _target_type: TargetType, _size: u64) {}
+
+    /// Bytes of a file that is hashed as a whole were read
+    fn on_bytes_processed(&self, _path: &Path, _bytes: u64) {}
+
+    /// A chunk of a file that is hashed in chunks was hashed
+    fn on_chunk_finished(&self, _path: &Path, _chunk: &HashedChunk) {}
+
+    /// A file was hashed completely
+    fn on_entry_finished(&self, _path: &Path, _entry: &HashedEntry) {}
+
+    /// A directory and all of its entries were hashed completely
+    fn on_directory_completed(&self, _path: &Path, _entry: &HashedEntry) {}
+
+    /// An error occurred that aborts the hashing process
+    fn on_error(&self, _path: &Path, _error: &Error) {}
+
+    /// Hashing of the target was cancelled before it completed
+    fn on_cancelled(&self, _path: &Path) {}
+}
+
+/// Forwards the events to the progress tracker thread
+impl HashObserver for Sender<InternalStateUpdate> {
+
+    fn on_entry_started(&self, path: &Path, target_type: TargetType, size: u64) {
+        // The progress tracker only keeps track of the files currently being hashed
+        if target_type == TargetType::FILE {
+            send_update(self, UpdateType::STARTED { size }, TargetType::FILE, None, path);
+        }
+    }
+
+    fn on_bytes_processed(&self, path: &Path, bytes: u64) {
+        send_update(self, UpdateType::PROGRESSED, TargetType::FILE, Some(bytes), path);
+    }
+
+    fn on_chunk_finished(&self, path: &Path, chunk: &HashedChunk) {
+        send_update(self, UpdateType::PROGRESSED, TargetType::FILE, Some(chunk.length), path);
+    }
+
+    /// The file's bytes were already reported while reading it
+    fn on_entry_finished(&self, path: &Path, _entry: &HashedEntry) {
+        send_update(self, UpdateType::FINISHED, TargetType::FILE, None, path);
+    }
+
+    fn on_directory_completed(&self, path: &Path, _entry: &HashedEntry) {
+        send_update(self, UpdateType::FINISHED, TargetType::DIRECTORY, None, path);
+    }
+
+    fn on_cancelled(&self, path: &Path) {
+        let target_type: TargetType = if path.is_dir() { TargetType::DIRECTORY } else { TargetType::FILE };
+        send_update(self, UpdateType::CANCELLED, target_type, None, path);
+    }
+}
+
+fn send_update(
+    tx: &Sender<InternalStateUpdate>,
+    update_type: UpdateType,
+    target_type: TargetType,
+    processed_bytes: Option<u64>,
+    path: &Path
+) {
+    tx.send(InternalStateUpdate {
+        update_type,
+        target_type,
+        processed_bytes,
+        path: path.to_path_buf()
+    }).unwrap_or(());
+}
@@ -1,32 +1,141 @@
 use std::{fs, thread};
+use std::collections::VecDeque;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
-use std::time::Duration;
-use crate::models::{InternalState, InternalStateUpdate, TargetType};
-use crate::util::error_exit;
-use crate::util::fs::{get_dir_entry, get_metadata, is_supported_filetype};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use crate::models::{InternalState, InternalStateUpdate, TargetType, TotalsStatus, UpdateType};
+use crate::util::fs::is_supported_filetype;
 use crate::util::math::ratio;
+use crate::util::size::format_size;
 
 const CLEAR_TERMINAL_LINE: &str = "\x1B[2K";
 const MOVE_UP_TERMINAL_LINE: &str = "\x1B[1A";
 const MOVE_TO_TERMINAL_LINE_START: &str = "\r";
 
+const TERMINAL_REFRESH_INTERVAL: Duration = Duration::from_millis(300);
+const PLAIN_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+const JSON_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Period over which the current throughput is averaged to smooth out short spikes and stalls
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Number of discovered entries after which the totals discovered so far are published
+const SCAN_PUBLISH_INTERVAL: u64 = 1000;
+
+/// Number of files currently being hashed that are shown in the terminal overview
+const DISPLAYED_FILES_IN_PROGRESS: usize = 3;
+
+/// Number of lines of the terminal overview, which are redrawn on every refresh
+const PROGRESS_LINE_COUNT: u64 = 5 + DISPLAYED_FILES_IN_PROGRESS as u64;
+
+/// How the progress is reported
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ProgressStyle {
+    /// Continuously redraw a detailed overview on stdout using ANSI escape codes
+    TERMINAL,
+    /// Periodically write a single line summarizing the progress, suitable for log files
+    PLAIN,
+    /// Periodically write newline-delimited JSON events
+    JSON,
+}
+
+#[derive(Serialize)]
+struct ProgressEvent<'a> {
+    event: &'a str,
+    elapsed_secs: f64,
+    files_processed: u64,
+    files_total: u64,
+    dirs_processed: u64,
+    dirs_total: u64,
+    bytes_processed: u64,
+    bytes_total: u64,
+    totals_status: TotalsStatus,
+    throughput: f64, // in bytes per second, averaged over the whole run
+    current_throughput: f64, // in bytes per second, averaged over the throughput window
+    eta_secs: Option<f64>,
+    current_path: Option<&'a PathBuf>,
+}
+
+/// Keeps track of the processed bytes over time to compute the current and average throughput
+struct ThroughputTracker {
+    start: Instant,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl ThroughputTracker {
+
+    fn new() -> Self {
+        ThroughputTracker { start: Instant::now(), samples: VecDeque::new() }
+    }
+
+    fn record(&mut self, processed_size: u64) {
+        let now: Instant = Instant::now();
+        self.samples.push_back((now, processed_size));
+
+        while self.samples.len() > 2 && now.duration_since(self.samples[0].0) > THROUGHPUT_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    fn get_elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Average throughput since the start, in bytes per second
+    fn get_average_throughput(&self) -> f64 {
+        let processed_size: u64 = self.samples.back().map(|sample| sample.1).unwrap_or(0);
+        bytes_per_second(processed_size, self.get_elapsed())
+    }
+
+    /// Throughput within the throughput window, in bytes per second
+    fn get_current_throughput(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => bytes_per_second(last.1 - first.1, last.0.duration_since(first.0)),
+            _ => 0.0
+        }
+    }
+
+    /// Estimated remaining time based on the current throughput, if any progress was made recently
+    fn get_eta(&self, remaining_size: u64) -> Option<Duration> {
+        let throughput: f64 = self.get_current_throughput();
+
+        if throughput > 0.0 {
+            Some(Duration::from_secs_f64(remaining_size as f64 / throughput))
+        } else if remaining_size == 0 {
+            Some(Duration::ZERO)
+        } else {
+            None
+        }
+    }
+}
 
 pub struct ProgressTracker {
-    internal_state: Arc<Mutex<InternalState>>
+    internal_state: Arc<Mutex<InternalState>>,
+    style: ProgressStyle,
+    sink: Option<Box<dyn Write + Send>>, // Destination of plain and JSON progress reports
 }
 
 impl ProgressTracker {
 
-    /// Create a ProgressTracker with initialized internal state
-    pub fn init(target: &PathBuf) -> Self {
-        let mut internal_state: InternalState = InternalState::default();
-        init_internal_state(target, &mut internal_state);
+    /// Create a ProgressTracker. If requested, the totals to process are discovered concurrently to
+    /// the hashing process, so the hashing does not have to wait for an additional traversal.
+    pub fn init(target: &Path, style: ProgressStyle, sink: Box<dyn Write + Send>, scan_totals: bool) -> Self {
+        let internal_state: Arc<Mutex<InternalState>> = Arc::new(Mutex::new(InternalState::default()));
+
+        if scan_totals {
+            internal_state.lock().unwrap().totals_status = TotalsStatus::SCANNING;
 
-        ProgressTracker { internal_state: Arc::new(Mutex::new(internal_state)) }
+            let target: PathBuf = target.to_path_buf();
+            let state_clone: Arc<Mutex<InternalState>> = Arc::clone(&internal_state);
+            thread::spawn(move || scan_totals_to_process(&target, state_clone));
+        }
+
+        ProgressTracker { internal_state, style, sink: Some(sink) }
     }
 
     /// Reads messages from the producer threads an updates the internal state accordingly
@@ -36,15 +145,31 @@ impl ProgressTracker {
         let running: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
         let running_clone: Arc<AtomicBool> = Arc::clone(&running);
         let state_clone: Arc<Mutex<InternalState>> = Arc::clone(&self.internal_state);
+        let style: ProgressStyle = self.style;
+        let sink: Box<dyn Write + Send> = self.sink.take().unwrap();
 
         let progress_printer = thread::spawn(move || {
-            progress_formatter(running_clone, state_clone);
+            match style {
+                ProgressStyle::TERMINAL => progress_formatter(running_clone, state_clone),
+                ProgressStyle::PLAIN | ProgressStyle::JSON => progress_reporter(running_clone, state_clone, style, sink),
+            }
         });
 
         for state_update in rx {
             self.update_internal_state(state_update);
         }
 
+        // Once everything is processed, the processed amounts are the totals, even if the scan for
+        // them did not complete yet
+        let mut state = self.internal_state.lock().unwrap();
+        if state.totals_status == TotalsStatus::SCANNING && !state.cancelled {
+            state.nr_of_files = state.nr_of_processed_files;
+            state.nr_of_sub_dirs = state.nr_of_processed_sub_dirs;
+            state.total_size_to_process = state.processed_size;
+            state.totals_status = TotalsStatus::COMPLETE;
+        }
+        drop(state);
+
         running.store(false, Ordering::Relaxed);
         progress_printer.join().unwrap_or_else(|_| {
             eprintln!("An error occurred while waiting for the progress printer thread!");
@@ -54,46 +179,197 @@ impl ProgressTracker {
     fn update_internal_state(&mut self, update: InternalStateUpdate) {
         let mut state = self.internal_state.lock().unwrap();
 
-        match update.processed_bytes {
-            Some(val) => state.processed_size += val,
-            None => {}
+        if let Some(val) = update.processed_bytes {
+            state.processed_size += val;
+        }
+
+        match update.update_type {
+            UpdateType::STARTED { size } => {
+                state.files_in_progress.insert(update.path, size);
+                return;
+            },
+            UpdateType::PROGRESSED => return,
+            UpdateType::CANCELLED => {
+                state.cancelled = true;
+                return;
+            },
+            UpdateType::FINISHED => {}
         }
 
         match update.target_type {
             TargetType::FILE => state.nr_of_processed_files += 1,
             TargetType::DIRECTORY => state.nr_of_processed_sub_dirs +=1
         }
+
+        state.files_in_progress.remove(&update.path);
+        state.last_processed_path = Some(update.path);
     }
 
 }
 
 fn progress_formatter(running: Arc<AtomicBool>, state: Arc<Mutex<InternalState>>) {
-    println!("\n\n");
+    let mut throughput: ThroughputTracker = ThroughputTracker::new();
+    print!("{}", "\n".repeat(PROGRESS_LINE_COUNT as usize));
 
     while running.load(Ordering::Relaxed) {
         let curr_state = state.lock().unwrap();
 
-        print_detailed_progress(&curr_state);
+        throughput.record(curr_state.processed_size);
+        print_detailed_progress(&curr_state, &throughput);
 
         drop(curr_state);
         std::io::stdout().flush().unwrap();
-        thread::sleep(Duration::from_millis(300));
+        thread::sleep(TERMINAL_REFRESH_INTERVAL);
     }
 
     std::io::stdout().flush().unwrap();
-    thread::sleep(Duration::from_millis(300));
-    clear_progress_lines(4);
+    thread::sleep(TERMINAL_REFRESH_INTERVAL);
+
+    // If cancelled, the overview is kept as statistics of what was processed until then
+    let curr_state = state.lock().unwrap();
+    if curr_state.cancelled {
+        throughput.record(curr_state.processed_size);
+        print_detailed_progress(&curr_state, &throughput);
+        std::io::stdout().flush().unwrap();
+    } else {
+        clear_progress_lines(PROGRESS_LINE_COUNT + 1);
+    }
+}
+
+/// Report the progress line by line instead of redrawing it, so the output can be consumed by
+/// other programs or written to log files
+fn progress_reporter(running: Arc<AtomicBool>, state: Arc<Mutex<InternalState>>, style: ProgressStyle, mut sink: Box<dyn Write + Send>) {
+    let mut throughput: ThroughputTracker = ThroughputTracker::new();
+    let mut last_report: Option<Instant> = None;
+
+    let interval: Duration = match style {
+        ProgressStyle::JSON => JSON_REFRESH_INTERVAL,
+        _ => PLAIN_REFRESH_INTERVAL
+    };
+
+    while running.load(Ordering::Relaxed) {
+        let curr_state = state.lock().unwrap();
+        throughput.record(curr_state.processed_size);
+
+        if last_report.is_none_or(|last_report| last_report.elapsed() >= interval) {
+            write_progress_report(&mut sink, &curr_state, &throughput, style, "progress");
+            last_report = Some(Instant::now());
+        }
+
+        // Sleep in short intervals to not delay the termination of the hashing process
+        drop(curr_state);
+        thread::sleep(TERMINAL_REFRESH_INTERVAL);
+    }
+
+    let curr_state = state.lock().unwrap();
+    throughput.record(curr_state.processed_size);
+
+    let event: &str = if curr_state.cancelled { "cancelled" } else { "finished" };
+    write_progress_report(&mut sink, &curr_state, &throughput, style, event);
+}
+
+fn write_progress_report(
+    sink: &mut Box<dyn Write + Send>,
+    curr_state: &MutexGuard<InternalState>,
+    throughput: &ThroughputTracker,
+    style: ProgressStyle,
+    event: &str
+) {
+    let elapsed: Duration = throughput.get_elapsed();
+    let eta: Option<Duration> = get_eta(curr_state, throughput);
+
+    let report: String = match style {
+        ProgressStyle::JSON => serde_json::to_string(&ProgressEvent {
+            event,
+            elapsed_secs: elapsed.as_secs_f64(),
+            files_processed: curr_state.nr_of_processed_files,
+            files_total: curr_state.nr_of_files,
+            dirs_processed: curr_state.nr_of_processed_sub_dirs,
+            dirs_total: curr_state.nr_of_sub_dirs,
+            bytes_processed: curr_state.processed_size,
+            bytes_total: curr_state.total_size_to_process,
+            totals_status: curr_state.totals_status,
+            throughput: throughput.get_average_throughput(),
+            current_throughput: throughput.get_current_throughput(),
+            eta_secs: eta.map(|eta| eta.as_secs_f64()),
+            current_path: curr_state.last_processed_path.as_ref()
+        }).unwrap_or_default(),
+        _ => format!(
+            "[{}] {} | {} | {} | {} | ETA {}",
+            format_duration(elapsed),
+            get_progress_metric("Files", curr_state.nr_of_processed_files, curr_state.nr_of_files, curr_state.totals_status),
+            get_progress_metric("Subdirs", curr_state.nr_of_processed_sub_dirs, curr_state.nr_of_sub_dirs, curr_state.totals_status),
+            get_progress_metric("Bytes", curr_state.processed_size, curr_state.total_size_to_process, curr_state.totals_status),
+            format_throughput(throughput.get_current_throughput()),
+            eta.map(format_duration).unwrap_or(String::from("unknown"))
+        )
+    };
+
+    // Failing to report the progress must not abort the hashing process
+    writeln!(sink, "{report}").and_then(|_| sink.flush()).unwrap_or(());
+}
+
+fn print_detailed_progress(curr_state: &MutexGuard<InternalState>, throughput: &ThroughputTracker) {
+    clear_progress_lines(PROGRESS_LINE_COUNT);
+    println!("{}", get_progress_metric("Processed files", curr_state.nr_of_processed_files, curr_state.nr_of_files, curr_state.totals_status));
+    println!("{}", get_progress_metric("Processed subdirs", curr_state.nr_of_processed_sub_dirs, curr_state.nr_of_sub_dirs, curr_state.totals_status));
+    println!("{}", get_progress_metric("Processed bytes", curr_state.processed_size, curr_state.total_size_to_process, curr_state.totals_status));
+    println!(
+        "Throughput:\t{} (average {})",
+        format_throughput(throughput.get_current_throughput()), format_throughput(throughput.get_average_throughput())
+    );
+    println!(
+        "Elapsed time:\t{} (remaining {})",
+        format_duration(throughput.get_elapsed()),
+        get_eta(curr_state, throughput).map(format_duration).unwrap_or(String::from("unknown"))
+    );
+
+    // Show the largest files currently being hashed, as these dominate the remaining time
+    let mut files_in_progress: Vec<(&PathBuf, &u64)> = curr_state.files_in_progress.iter().collect();
+    files_in_progress.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    for i in 0..DISPLAYED_FILES_IN_PROGRESS {
+        match files_in_progress.get(i) {
+            Some((path, size)) => println!("Hashing:\t{} ({})", path.to_string_lossy(), format_size(**size)),
+            None => println!()
+        }
+    }
+}
+
+/// The remaining time can only be estimated once the total size to process is known
+fn get_eta(curr_state: &MutexGuard<InternalState>, throughput: &ThroughputTracker) -> Option<Duration> {
+    if curr_state.totals_status != TotalsStatus::COMPLETE {
+        return None;
+    }
+
+    throughput.get_eta(curr_state.total_size_to_process.saturating_sub(curr_state.processed_size))
+}
+
+fn bytes_per_second(bytes: u64, duration: Duration) -> f64 {
+    if duration.as_secs_f64() > 0.0 {
+        bytes as f64 / duration.as_secs_f64()
+    } else {
+        0.0
+    }
+}
+
+fn format_throughput(bytes_per_second: f64) -> String {
+    format!("{}/s", format_size(bytes_per_second as u64))
 }
 
-fn print_detailed_progress(curr_state: &MutexGuard<InternalState>) {
-    clear_progress_lines(3);
-    println!("{}", get_progress_metric("Processed files", curr_state.nr_of_processed_files, curr_state.nr_of_files));
-    println!("{}", get_progress_metric("Processed subdirs", curr_state.nr_of_processed_sub_dirs, curr_state.nr_of_sub_dirs));
-    println!("{}", get_progress_metric("Processed bytes", curr_state.processed_size, curr_state.total_size_to_process));
+fn format_duration(duration: Duration) -> String {
+    let secs: u64 = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
 }
 
-fn get_progress_metric(info: &str, processed: u64, total: u64) -> String {
-    format!("{info}:\t{processed}/{total} ({:.4}%)", ratio(processed, total).unwrap_or_else(|| 0.0) * 100.0)
+fn get_progress_metric(info: &str, processed: u64, total: u64, totals_status: TotalsStatus) -> String {
+    match totals_status {
+        TotalsStatus::COMPLETE => {
+            format!("{info}:\t{processed}/{total} ({:.4}%)", ratio(processed, total).unwrap_or(0.0) * 100.0)
+        },
+        TotalsStatus::SCANNING => format!("{info}:\t{processed} (≥ {total} discovered)"),
+        TotalsStatus::SKIPPED => format!("{info}:\t{processed}"),
+    }
 }
 
 fn clear_progress_lines(line_count: u64) {
@@ -102,35 +378,89 @@ fn clear_progress_lines(line_count: u64) {
     }
 }
 
-fn init_internal_state(path: &PathBuf, state: &mut InternalState) {
+fn scan_totals_to_process(target: &PathBuf, state: Arc<Mutex<InternalState>>) {
+    let mut discovered: InternalState = InternalState::default();
 
-    if path.is_file() {
-        add_file_impact_to_state(path, state);
-        return;
+    // The target directory itself is reported as processed once hashed as well
+    if target.is_dir() {
+        add_sub_dir_impact_to_state(&mut discovered);
     }
 
-    let entries = fs::read_dir(path).unwrap_or_else(|e| {
-        error_exit(Some(format!("Unable to unwrap ReadDir iterator over '{:?}': {e:?}", path)));
-    });
+    match init_internal_state(target, &mut discovered, &state) {
+        Ok(true) => if publish_discovered_totals(&mut discovered, &state) {
+            state.lock().unwrap().totals_status = TotalsStatus::COMPLETE;
+        },
+        Ok(false) => {},
 
-    for entry in entries.map(|e| get_dir_entry(path, e)) {
+        // Entries may vanish or become unreadable while the target is hashed. Such errors are
+        // reported by the hash computation itself, the scan merely gives up on the totals.
+        Err(_) => {
+            let mut state = state.lock().unwrap();
 
-        if !is_supported_filetype(&entry.path()) {
-            continue
-        } else if entry.path().is_file() {
-            add_file_impact_to_state(&entry.path(), state);
-            continue
-        } else if entry.path().is_dir() {
-            add_sub_dir_impact_to_state(state);
-            init_internal_state(&entry.path(), state);
+            if state.totals_status == TotalsStatus::SCANNING {
+                state.nr_of_files = 0;
+                state.nr_of_sub_dirs = 0;
+                state.total_size_to_process = 0;
+                state.totals_status = TotalsStatus::SKIPPED;
+            }
+        }
+    }
+}
+
+/// Traverse the target and periodically publish the discovered totals. Returns false if the scan
+/// was aborted because the totals are no longer needed.
+fn init_internal_state(path: &PathBuf, discovered: &mut InternalState, state: &Arc<Mutex<InternalState>>) -> std::io::Result<bool> {
+
+    if path.is_file() {
+        add_file_impact_to_state(path, discovered)?;
+        return Ok(true);
+    }
+
+    for entry in fs::read_dir(path)? {
+        let entry_path: PathBuf = entry?.path();
+
+        if !is_supported_filetype(&entry_path) {
             continue
+        } else if entry_path.is_file() {
+            add_file_impact_to_state(&entry_path, discovered)?;
+        } else if entry_path.is_dir() {
+            add_sub_dir_impact_to_state(discovered);
+
+            if !init_internal_state(&entry_path, discovered, state)? {
+                return Ok(false);
+            }
         }
+
+        let pending_entries: u64 = discovered.nr_of_files + discovered.nr_of_sub_dirs;
+        if pending_entries >= SCAN_PUBLISH_INTERVAL && !publish_discovered_totals(discovered, state) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Add the discovered totals to the shared state and reset them. Returns false if the totals are
+/// no longer needed.
+fn publish_discovered_totals(discovered: &mut InternalState, state: &Arc<Mutex<InternalState>>) -> bool {
+    let mut state = state.lock().unwrap();
+
+    if state.totals_status != TotalsStatus::SCANNING {
+        return false;
     }
+
+    state.nr_of_files += discovered.nr_of_files;
+    state.nr_of_sub_dirs += discovered.nr_of_sub_dirs;
+    state.total_size_to_process += discovered.total_size_to_process;
+
+    *discovered = InternalState::default();
+    true
 }
 
-fn add_file_impact_to_state(path: &PathBuf, state: &mut InternalState) {
+fn add_file_impact_to_state(path: &Path, state: &mut InternalState) -> std::io::Result<()> {
     state.nr_of_files += 1;
-    state.total_size_to_process += get_metadata(path).len();
+    state.total_size_to_process += path.metadata()?.len();
+    Ok(())
 }
 
 fn add_sub_dir_impact_to_state(state: &mut InternalState) {
@@ -0,0 +1,121 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::hasher::HasherFactory;
+use crate::merkle_tree::MerkleTree;
+use crate::models::{HashFunctionType, HashedEntry, HashingConfig, XofParameters};
+
+/// Version of the snapshot file format, bumped whenever the layout changes incompatibly
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Persistable representation of a fully hashed target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashTreeSnapshot {
+    pub version: u32,
+    pub algorithm: HashFunctionType,
+    pub config: HashingConfig,
+    pub root: HashedEntry,
+}
+
+impl HashTreeSnapshot {
+
+    pub fn new(algorithm: HashFunctionType, config: HashingConfig, root: HashedEntry) -> Self {
+        HashTreeSnapshot {
+            version: SNAPSHOT_FORMAT_VERSION,
+            algorithm,
+            config,
+            root
+        }
+    }
+
+    pub fn get_root_hash(&self) -> &Vec<u8> {
+        &self.root.hash
+    }
+}
+
+/// Write the snapshot as compact JSON to the given file
+pub fn export_hash_tree(path: &Path, snapshot: &HashTreeSnapshot) -> std::io::Result<()> {
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+
+    serde_json::to_writer(&mut writer, snapshot).map_err(|e| {
+        Error::other(format!("Unable to serialize hash tree: {e:?}"))
+    })?;
+
+    writer.flush()
+}
+
+/// Load a previously exported snapshot and verify that every stored hash value matches the root
+/// of the merkle tree rebuilt from its leaves. Snapshots computed with a key can only be verified
+/// with the same key.
+pub fn import_hash_tree(path: &Path, key: Option<&[u8]>) -> std::io::Result<HashTreeSnapshot> {
+    let reader: BufReader<File> = BufReader::new(File::open(path)?);
+
+    let snapshot: HashTreeSnapshot = serde_json::from_reader(reader).map_err(|e| {
+        Error::new(ErrorKind::InvalidData, format!("Unable to parse hash tree: {e:?}"))
+    })?;
+
+    if snapshot.version != SNAPSHOT_FORMAT_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unsupported hash tree format version {} (expected {SNAPSHOT_FORMAT_VERSION})", snapshot.version)
+        ));
+    }
+
+    if snapshot.config.keyed != key.is_some() {
+        let keyed: &str = if snapshot.config.keyed { "with" } else { "without" };
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("The hash tree was computed {keyed} a key, which has to be used for verifying it as well")
+        ));
+    }
+
+    HasherFactory::validate_xof_parameters(&snapshot.algorithm, &snapshot.config.xof)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    validate_entry(&snapshot.root, &snapshot.algorithm, &snapshot.config.xof, key, &snapshot.root.name)?;
+
+    Ok(snapshot)
+}
+
+/// Rebuild the merkle tree of the given entry from its stored leaves. Fails if the hash function
+/// does not support the key.
+pub fn get_merkle_tree(
+    entry: &HashedEntry,
+    algorithm: &HashFunctionType,
+    xof: &XofParameters,
+    key: Option<&[u8]>
+) -> Result<Option<MerkleTree>, String> {
+    let Some(leaves) = entry.get_leaf_hashes() else {
+        return Ok(None);
+    };
+
+    let mut tree: MerkleTree = MerkleTree::new(algorithm, xof, key)?;
+    tree.initialize_from_vector(&leaves);
+    Ok(Some(tree))
+}
+
+fn validate_entry(
+    entry: &HashedEntry,
+    algorithm: &HashFunctionType,
+    xof: &XofParameters,
+    key: Option<&[u8]>,
+    path: &str
+) -> std::io::Result<()> {
+
+    let tree: Option<MerkleTree> = get_merkle_tree(entry, algorithm, xof, key)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+    if let Some(tree) = tree && tree.get_root_hash() != entry.hash {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Stored hash of '{path}' does not match the root of its merkle tree")
+        ));
+    }
+
+    for child in &entry.children {
+        validate_entry(child, algorithm, xof, key, &format!("{path}/{}", child.name))?;
+    }
+
+    Ok(())
+}
@@ -1,23 +1,18 @@
 use std::fs::{DirEntry, File, Metadata};
 use std::io::{Error, Read, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::util::error_exit;
 
-pub fn read_chunk(path: &PathBuf, start: u64, end: u64) -> Vec<u8> {
-    let mut file: File = get_file(path);
+/// Read the bytes from start (inclusive) to end (exclusive) of the file, or fewer if the file ends
+/// before
+pub fn read_chunk(path: &PathBuf, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+    let mut file: File = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
 
-    file.seek(SeekFrom::Start(start)).unwrap_or_else(|e| {
-        error_exit(Some(format!("Error seeking chunk ({start}, {end}) in file '{:?}': {e:?} ", path)));
-    });
+    let mut buffer: Vec<u8> = Vec::with_capacity((end-start) as usize);
+    file.take(end - start).read_to_end(&mut buffer)?;
 
-    let mut buffer: Vec<u8> = vec![0u8; (end-start) as usize];
-
-    let bytes_read = file.read(&mut buffer).unwrap_or_else(|e| {
-        error_exit(Some(format!("Unable to read chunk ({start}, {end}) from file '{:?}': {e:?}", path)));
-    });
-    buffer.truncate(bytes_read);
-
-    buffer
+    Ok(buffer)
 }
 
 pub fn get_file(path: &PathBuf) -> File {
@@ -41,6 +36,22 @@ pub fn get_dir_entry(path: &PathBuf, entry_res: Result<DirEntry, Error>) -> DirE
     }
 }
 
-pub fn is_supported_filetype(path: &PathBuf) -> bool {
+pub fn is_supported_filetype(path: &Path) -> bool {
     path.is_dir() || path.is_file()
 }
+
+pub fn get_entry_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => path.to_string_lossy().to_string()
+    }
+}
+
+/// Path of an entry relative to the hashed root, using '/' as separator on every platform
+pub fn join_relative_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{parent}/{name}")
+    }
+}
@@ -2,13 +2,18 @@ use std::process;
 
 pub mod fs;
 pub mod math;
+pub mod size;
 
 pub const PAR_HASH_DEFAULT_ERROR_CODE: i32 = 1;
+pub const PAR_HASH_CANCELLED_ERROR_CODE: i32 = 130;
 
 pub fn error_exit(msg: Option<String>) -> ! {
-    match msg {
-        Some(msg) => eprintln!("{}", msg),
-        _ => {}
+    error_exit_with_code(PAR_HASH_DEFAULT_ERROR_CODE, msg)
+}
+
+pub fn error_exit_with_code(code: i32, msg: Option<String>) -> ! {
+    if let Some(msg) = msg {
+        eprintln!("{}", msg);
     }
-    process::exit(PAR_HASH_DEFAULT_ERROR_CODE)
+    process::exit(code)
 }
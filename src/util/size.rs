@@ -0,0 +1,140 @@
+use std::num::{IntErrorKind, ParseIntError};
+
+/// Largest supported chunk size, as every chunk is read into memory at once
+pub const MAX_CHUNK_SIZE: u64 = 1 << 30; // in bytes
+
+/// Units are case-insensitive and may be followed by "B". Decimal units (k, M, G, T) are powers of
+/// 1000, binary units (Ki, Mi, Gi, Ti) powers of 1024.
+const SIZE_UNITS: [(&str, u64); 9] = [
+    ("", 1),
+    ("k", 1_000),
+    ("m", 1_000_000),
+    ("g", 1_000_000_000),
+    ("t", 1_000_000_000_000),
+    ("ki", 1 << 10),
+    ("mi", 1 << 20),
+    ("gi", 1 << 30),
+    ("ti", 1 << 40),
+];
+
+const BINARY_UNIT_NAMES: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+/// Parse a size such as "4096", "512k", "64MiB" or "1.5G" into bytes
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value: &str = value.trim();
+    let unit_start: usize = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+
+    let unit: String = unit.trim().to_lowercase();
+    let unit: &str = unit.strip_suffix('b').unwrap_or(&unit);
+
+    let multiplier: u64 = SIZE_UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, multiplier)| *multiplier)
+        .ok_or(format!("'{value}' has an unknown unit, expected one of k, M, G, T, KiB, MiB, GiB or TiB"))?;
+
+    // Integers are computed exactly, as f64 cannot represent every integer above 2^53
+    if !number.contains('.') {
+        let number: u64 = number.parse().map_err(|e: ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow => format!("'{value}' is too large"),
+            _ => format!("'{value}' does not start with a number")
+        })?;
+
+        return number.checked_mul(multiplier).ok_or(format!("'{value}' is too large"));
+    }
+
+    let number: f64 = number.parse().map_err(|_| format!("'{value}' does not start with a number"))?;
+    let bytes: f64 = number * multiplier as f64;
+
+    if bytes.fract() != 0.0 {
+        return Err(format!("'{value}' does not amount to a whole number of bytes"));
+    }
+    if bytes > u64::MAX as f64 {
+        return Err(format!("'{value}' is too large"));
+    }
+
+    Ok(bytes as u64)
+}
+
+pub fn validate_split_size(split_size: u64) -> Result<(), String> {
+    if split_size == 0 {
+        return Err(String::from("Split threshold must not be zero, as even empty files would be split"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_chunk_size(chunk_size: u64) -> Result<(), String> {
+    if chunk_size == 0 {
+        return Err(String::from("Chunk size must not be zero"));
+    }
+
+    if chunk_size > MAX_CHUNK_SIZE {
+        return Err(format!(
+            "Chunk size of {} exceeds the maximum of {}, as every chunk is read into memory at once",
+            format_size(chunk_size), format_size(MAX_CHUNK_SIZE)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Format the size using the largest binary unit it amounts to at least one of
+pub fn format_size(bytes: u64) -> String {
+    let exponent: usize = (1..BINARY_UNIT_NAMES.len()).rev().find(|i| bytes >= 1 << (10 * i)).unwrap_or(0);
+
+    if exponent == 0 {
+        return format!("{bytes} B");
+    }
+
+    let value: f64 = bytes as f64 / (1u64 << (10 * exponent)) as f64;
+    format!("{value:.2} {}", BINARY_UNIT_NAMES[exponent])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_numbers_and_units() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size(" 512k "), Ok(512_000));
+        assert_eq!(parse_size("64MiB"), Ok(64 << 20));
+        assert_eq!(parse_size("1.5G"), Ok(1_500_000_000));
+        assert_eq!(parse_size("2 tb"), Ok(2_000_000_000_000));
+    }
+
+    #[test]
+    fn parses_large_integers_exactly() {
+        assert_eq!(parse_size("9007199254740993"), Ok(9_007_199_254_740_993));
+        assert_eq!(parse_size("18446744073709551615"), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("k").is_err());
+        assert!(parse_size("12x").is_err());
+        assert!(parse_size("0.5").is_err());
+        assert!(parse_size("18446744073709551616").is_err());
+        assert!(parse_size("20000000TiB").is_err());
+    }
+
+    #[test]
+    fn validates_split_and_chunk_sizes() {
+        assert!(validate_split_size(0).is_err());
+        assert!(validate_split_size(1).is_ok());
+        assert!(validate_chunk_size(0).is_err());
+        assert!(validate_chunk_size(MAX_CHUNK_SIZE).is_ok());
+        assert!(validate_chunk_size(MAX_CHUNK_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn formats_sizes_in_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.50 KiB");
+        assert_eq!(format_size(1 << 30), "1.00 GiB");
+    }
+}